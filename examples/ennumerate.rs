@@ -34,16 +34,16 @@ fn get_all_tiles() -> Vec<Suit> {
     let mut vec = vec![];
 
     for suit in [Suit::Dots, Suit::Bamboo, Suit::Characters] {
-        for i in 0..0xA {
+        for i in (1..=9).chain([RED_FIVE]) {
             vec.push(suit(i));
         }
     }
 
-    for wind in [Wind::South, Wind::East, Wind::North, Wind::West] {
+    for wind in [Wind::East, Wind::South, Wind::West, Wind::North] {
         vec.push(Suit::Wind(wind));
     }
 
-    for dragon in [Dragon::White, Dragon::Red, Dragon::Green] {
+    for dragon in [Dragon::White, Dragon::Green, Dragon::Red] {
         vec.push(Suit::Dragon(dragon));
     }
 
@@ -0,0 +1,195 @@
+use crate::*;
+
+/// The second check character is taken modulo a prime, so a single mistyped character
+/// can be traced back to its position
+const POSITION_MODULUS: i32 = 61;
+
+/// The two check characters for `values`: their sum modulo 64, and their sum weighted
+/// by position modulo [POSITION_MODULUS]
+fn check_values(values: &[u8]) -> [u8; 2] {
+    let (sum, weighted) = values
+        .iter()
+        .enumerate()
+        .fold((0, 0), |(sum, weighted), (i, value)| {
+            (
+                (sum + *value as i32) % 64,
+                (weighted + (i as i32 + 1) * *value as i32) % POSITION_MODULUS,
+            )
+        });
+
+    [sum as u8, weighted as u8]
+}
+
+/// Appends two check characters to `text`, which must only hold characters from
+/// [ALPHABET]
+pub(crate) fn append(text: &mut String) {
+    let values = text.bytes().filter_map(value_of).collect::<Vec<_>>();
+
+    for value in check_values(&values) {
+        text.push(ALPHABET[value as usize] as char);
+    }
+}
+
+/// Checks the two characters at the end of `input`, returning the input without them.
+/// Can throw a [DecodeErr] pointing at the character that was most likely mistyped
+pub(crate) fn verify(input: &str) -> Result<&str, DecodeErr> {
+    let values = input
+        .bytes()
+        .enumerate()
+        .map(|(position, byte)| {
+            value_of(byte).ok_or(DecodeErr::InvalidCharacter {
+                byte,
+                position,
+                expected: EXPECTED_ALPHABET,
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if values.len() < 2 {
+        return Err(DecodeErr::UnexpectedEnd {
+            position: values.len(),
+        });
+    }
+
+    let (body, check) = values.split_at(values.len() - 2);
+    let [sum, weighted] = check_values(body);
+
+    let sum_off = (sum as i32 - check[0] as i32).rem_euclid(64);
+    let weighted_off = (weighted as i32 - check[1] as i32).rem_euclid(POSITION_MODULUS);
+
+    if (sum_off, weighted_off) == (0, 0) {
+        return Ok(&input[..body.len()]);
+    }
+
+    Err(DecodeErr::ChecksumMismatch {
+        likely_position: mistyped(body, sum_off, weighted_off),
+    })
+}
+
+/// Finds the single character that, if it was off by some amount, would explain
+/// both check characters being off. The sum only gives that amount modulo 64 and
+/// the weighted sum only gives the position modulo [POSITION_MODULUS], so when more
+/// than one character could have been mistyped there is no telling which one it was
+fn mistyped(body: &[u8], sum_off: i32, weighted_off: i32) -> Option<usize> {
+    // A mistyped check character only throws off its own check
+    let checks =
+        [(weighted_off == 0).then_some(body.len()), (sum_off == 0).then_some(body.len() + 1)];
+
+    let candidates = body
+        .iter()
+        .enumerate()
+        .flat_map(|(position, value)| {
+            [sum_off, sum_off - 64].into_iter().filter_map(move |diff| {
+                let weight = position as i32 + 1;
+                let original = *value as i32 - diff;
+
+                ((diff * weight - weighted_off).rem_euclid(POSITION_MODULUS) == 0
+                    && (0..64).contains(&original))
+                .then_some(position)
+            })
+        })
+        .chain(checks.into_iter().flatten())
+        .collect::<Vec<_>>();
+
+    match candidates[..] {
+        [position] => Some(position),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn checked(text: &str) -> String {
+        let mut text = text.to_string();
+        append(&mut text);
+        text
+    }
+
+    fn typo(text: &str, position: usize, byte: u8) -> String {
+        let mut bytes = text.as_bytes().to_vec();
+        bytes[position] = byte;
+        String::from_utf8(bytes).unwrap()
+    }
+
+    #[test]
+    fn accepts_untouched_input() {
+        let text = checked("yz0123UVWXXklm");
+
+        assert_eq!(text.len(), 16);
+        assert_eq!(verify(&text), Ok("yz0123UVWXXklm"));
+    }
+
+    #[test]
+    fn points_at_any_single_mistyped_character() {
+        let text = checked("yz0123UVWXXklm");
+        let mut found = 0;
+
+        for position in 0..text.len() {
+            for &byte in ALPHABET
+                .iter()
+                .filter(|byte| **byte != text.as_bytes()[position])
+            {
+                match verify(&typo(&text, position, byte)) {
+                    Err(DecodeErr::ChecksumMismatch {
+                        likely_position: Some(likely),
+                    }) => {
+                        assert_eq!(likely, position);
+                        found += 1;
+                    }
+                    Err(DecodeErr::ChecksumMismatch {
+                        likely_position: None,
+                    }) => {}
+                    other => panic!("{other:?} for a typo at {position}"),
+                }
+            }
+        }
+
+        assert!(found * 10 > text.len() * 63 * 9);
+    }
+
+    /// Every single character typo that is given a position is given the right one
+    fn assert_never_misplaced(text: &str) {
+        for position in 0..text.len() {
+            for &byte in ALPHABET
+                .iter()
+                .filter(|byte| **byte != text.as_bytes()[position])
+            {
+                match verify(&typo(text, position, byte)) {
+                    Err(DecodeErr::ChecksumMismatch { likely_position }) => assert!(
+                        likely_position.is_none_or(|likely| likely == position),
+                        "{likely_position:?} for a typo at {position}"
+                    ),
+                    other => panic!("{other:?} for a typo at {position}"),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn never_blames_the_wrong_character() {
+        // values 0-2 and 61-63, where a typo can be off by 61
+        assert_never_misplaced(&checked("ABC9+/ABC9+/"));
+        // long enough that positions 61 apart share a weight
+        assert_never_misplaced(&checked(&"ABC9+/".repeat(12)));
+        assert_never_misplaced("APABBBAAAAAAAAAAASt");
+        assert_eq!(
+            verify(&typo("APABBBAAAAAAAAAAASt", 2, b'9')),
+            Err(DecodeErr::ChecksumMismatch {
+                likely_position: None
+            })
+        );
+    }
+
+    #[test]
+    fn catches_swapped_characters() {
+        let text = checked("yz0123UVWXXklm");
+        let swapped = typo(&typo(&text, 3, b'2'), 4, b'1');
+
+        assert!(matches!(
+            verify(&swapped),
+            Err(DecodeErr::ChecksumMismatch { .. })
+        ));
+    }
+}
@@ -0,0 +1,315 @@
+use crate::*;
+
+/// The most tiles a compact encoding can hold, a hand with four kans
+pub const COMPACT_MAX_TILES: usize = 18;
+
+/// Red fives are counted `0`–`4` for each numbered suit, giving this many combinations
+const RED_COMBINATIONS: u64 = 125;
+
+/// `WAYS[kinds][tiles]` is how many hands of `tiles` tiles can be made from `kinds`
+/// kinds of tile, with at most four of each
+const WAYS: [[u64; COMPACT_MAX_TILES + 1]; TILE_KINDS + 1] = {
+    let mut ways = [[0; COMPACT_MAX_TILES + 1]; TILE_KINDS + 1];
+    ways[0][0] = 1;
+
+    let mut kinds = 1;
+    while kinds <= TILE_KINDS {
+        let mut tiles = 0;
+        while tiles <= COMPACT_MAX_TILES {
+            let mut copies = 0;
+            while copies <= 4 && copies <= tiles {
+                ways[kinds][tiles] += ways[kinds - 1][tiles - copies];
+                copies += 1;
+            }
+            tiles += 1;
+        }
+        kinds += 1;
+    }
+
+    ways
+};
+
+/// How many bytes the packed value of a hand of `tiles` tiles takes up
+const fn packed_len(tiles: usize) -> usize {
+    let largest = WAYS[TILE_KINDS][tiles] * RED_COMBINATIONS - 1;
+    (u64::BITS - largest.leading_zeros()).div_ceil(8) as usize
+}
+
+impl TileCounts {
+    /// Packs the hand into as few bytes as possible: the number of tiles, followed by
+    /// the position of the hand among every possible hand of that many tiles, with
+    /// the red fives folded in. A 14 tile hand takes up 7 bytes.
+    /// Can throw a [DecodeErr] if there are more than [COMPACT_MAX_TILES] tiles
+    ///
+    /// ```
+    /// # use mahjong_encoding::*;
+    /// let counts = TileCounts::from_tiles(&Suit::from_mpsz("123m406p789s11z555s").unwrap()).unwrap();
+    /// let packed = counts.to_compact().unwrap();
+    ///
+    /// assert_eq!(packed.len(), 7);
+    /// assert_eq!(TileCounts::from_compact(&packed), Ok(counts));
+    /// ```
+    pub fn to_compact(&self) -> Result<Vec<u8>, DecodeErr> {
+        let length = self.len();
+        if length > COMPACT_MAX_TILES {
+            return Err(DecodeErr::InvalidHandLength { length });
+        }
+
+        let mut rank = 0;
+        let mut remaining = length;
+        for (index, count) in self.counts().iter().enumerate() {
+            let kinds_after = TILE_KINDS - index - 1;
+            for copies in 0..*count as usize {
+                rank += WAYS[kinds_after][remaining - copies];
+            }
+            remaining -= *count as usize;
+        }
+
+        let red = self
+            .red_fives()
+            .iter()
+            .fold(0, |red, count| red * 5 + *count as u64);
+        let value = rank * RED_COMBINATIONS + red;
+
+        let mut packed = vec![length as u8];
+        packed.extend_from_slice(&value.to_be_bytes()[8 - packed_len(length)..]);
+        Ok(packed)
+    }
+
+    /// Unpacks a hand written by [TileCounts::to_compact]. Can throw a [DecodeErr]
+    pub fn from_compact(packed: &[u8]) -> Result<TileCounts, DecodeErr> {
+        let (&length, value) = packed
+            .split_first()
+            .ok_or(DecodeErr::UnexpectedEnd { position: 0 })?;
+        let length = length as usize;
+        if length > COMPACT_MAX_TILES {
+            return Err(DecodeErr::InvalidHandLength { length });
+        }
+        if value.len() != packed_len(length) {
+            return Err(DecodeErr::InvalidPacking);
+        }
+
+        let value = value
+            .iter()
+            .fold(0, |value, byte| value << 8 | *byte as u64);
+        let mut rank = value / RED_COMBINATIONS;
+        if rank >= WAYS[TILE_KINDS][length] {
+            return Err(DecodeErr::InvalidPacking);
+        }
+
+        let mut counts = [0; TILE_KINDS];
+        let mut remaining = length;
+        for (index, count) in counts.iter_mut().enumerate() {
+            let kinds_after = TILE_KINDS - index - 1;
+            while (*count as usize) < remaining.min(4) {
+                let ways = WAYS[kinds_after][remaining - *count as usize];
+                if rank < ways {
+                    break;
+                }
+                rank -= ways;
+                *count += 1;
+            }
+            remaining -= *count as usize;
+        }
+
+        let mut red = value % RED_COMBINATIONS;
+        let mut hand = TileCounts::from_counts(counts)?.to_tiles();
+        for suit in (0..3).rev() {
+            let reds = (red % 5) as usize;
+            red /= 5;
+
+            let five = Suit::from_index(suit * 9 + 4).unwrap();
+            let mut fives = hand.iter_mut().filter(|tile| **tile == five);
+            for _ in 0..reds {
+                *fives.next().ok_or(DecodeErr::InvalidPacking)? = five.to_red();
+            }
+        }
+
+        TileCounts::from_tiles(&hand)
+    }
+}
+
+impl Suit {
+    /// Packs a hand into bytes, see [TileCounts::to_compact]. The order of the tiles
+    /// isn't kept. Can throw a [DecodeErr]
+    pub fn to_compact(hand: &[Suit]) -> Result<Vec<u8>, DecodeErr> {
+        TileCounts::from_tiles(hand)?.to_compact()
+    }
+
+    /// Unpacks a hand written by [Suit::to_compact], in canonical order.
+    /// Can throw a [DecodeErr]
+    pub fn from_compact(packed: &[u8]) -> Result<Vec<Suit>, DecodeErr> {
+        Ok(TileCounts::from_compact(packed)?.to_tiles())
+    }
+
+    /// Packs a hand into a string using [Alphabet::UrlSafe], safe to use in links and
+    /// QR codes. Can throw a [DecodeErr]
+    ///
+    /// ```
+    /// # use mahjong_encoding::*;
+    /// let hand = Suit::from_mpsz("123m406p789s11z555s").unwrap();
+    /// let text = Suit::to_compact_string(&hand).unwrap();
+    ///
+    /// assert_eq!(text.len(), 10);
+    /// assert_eq!(Suit::from_compact_string(&text).unwrap().len(), 14);
+    /// ```
+    pub fn to_compact_string(hand: &[Suit]) -> Result<String, DecodeErr> {
+        let packed = Suit::to_compact(hand)?;
+        let mut text = String::new();
+
+        for chunk in packed.chunks(3) {
+            let bits = chunk
+                .iter()
+                .chain([0, 0].iter())
+                .take(3)
+                .fold(0u32, |bits, byte| bits << 8 | *byte as u32);
+
+            for i in 0..=chunk.len() {
+                let value = (bits >> (18 - 6 * i) & 0x3F) as u8;
+                text.push(Alphabet::UrlSafe.encode(value) as char);
+            }
+        }
+
+        Ok(text)
+    }
+
+    /// Unpacks a hand written by [Suit::to_compact_string], in canonical order. Either
+    /// [Alphabet] is accepted, but otherwise the string must be exactly the one that
+    /// was written. Can throw a [DecodeErr]
+    pub fn from_compact_string(input: &str) -> Result<Vec<Suit>, DecodeErr> {
+        let mut packed = vec![];
+        let mut bits = 0u32;
+        let mut count = 0;
+
+        for (position, byte) in input.bytes().enumerate() {
+            let value = value_of(byte).ok_or(DecodeErr::InvalidCharacter {
+                byte,
+                position,
+                expected: EXPECTED_ALPHABET,
+            })?;
+
+            bits = bits << 6 | value as u32;
+            count += 6;
+            if count >= 8 {
+                count -= 8;
+                packed.push((bits >> count) as u8);
+            }
+        }
+
+        // Only the string written by `to_compact_string` is accepted, without a
+        // character left over or anything in the padding bits
+        if count >= 6 || bits & ((1 << count) - 1) != 0 {
+            return Err(DecodeErr::InvalidPacking);
+        }
+
+        Suit::from_compact(&packed)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test::xorshift;
+
+    #[test]
+    fn counts_every_hand() {
+        assert_eq!(WAYS[TILE_KINDS][0], 1);
+        assert_eq!(WAYS[TILE_KINDS][1], 34);
+        assert_eq!(WAYS[1][4], 1);
+        assert_eq!(WAYS[1][5], 0);
+        assert_eq!(WAYS[2][5], 4);
+    }
+
+    #[test]
+    fn round_trips_random_hands() {
+        let mut state = 0x2545F4914F6CDD1Du64;
+        let mut next = || xorshift(&mut state);
+
+        for _ in 0..500 {
+            let length = next() as usize % (COMPACT_MAX_TILES + 1);
+            let mut counts = TileCounts::new();
+            while counts.len() < length {
+                let tile = Suit::from_index(next() as usize % TILE_KINDS).unwrap();
+                let tile = match next() % 3 {
+                    0 => tile.to_red(),
+                    _ => tile,
+                };
+                counts.add(tile);
+            }
+
+            let hand = counts.to_tiles();
+            let text = Suit::to_compact_string(&hand).unwrap();
+            assert_eq!(Suit::from_compact_string(&text), Ok(hand));
+        }
+    }
+
+    #[test]
+    fn packs_the_first_and_last_hands() {
+        let first = Suit::from_mpsz("44555566667777z").unwrap();
+        let last = Suit::from_mpsz("11112222333344m").unwrap();
+
+        assert_eq!(Suit::to_compact(&first).unwrap(), [14, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(
+            Suit::to_compact(&last).unwrap()[1..],
+            ((WAYS[TILE_KINDS][14] - 1) * RED_COMBINATIONS).to_be_bytes()[2..]
+        );
+        assert_eq!(
+            Suit::from_compact(&Suit::to_compact(&last).unwrap()),
+            Ok(last)
+        );
+    }
+
+    #[test]
+    fn rejects_bad_packing() {
+        assert_eq!(
+            Suit::from_compact(&[]),
+            Err(DecodeErr::UnexpectedEnd { position: 0 })
+        );
+        assert_eq!(Suit::from_compact(&[14, 0]), Err(DecodeErr::InvalidPacking));
+        assert_eq!(
+            Suit::from_compact(&[14, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]),
+            Err(DecodeErr::InvalidPacking)
+        );
+        // a red five in a hand without any fives
+        assert_eq!(
+            Suit::from_compact(&[1, 0, 1]),
+            Err(DecodeErr::InvalidPacking)
+        );
+        assert_eq!(
+            Suit::to_compact(&[Suit::Dragon(Dragon::Red); 19]),
+            Err(DecodeErr::TooManyCopies {
+                tile: Suit::Dragon(Dragon::Red),
+                count: 19
+            })
+        );
+        assert!(matches!(
+            Suit::from_compact_string("A!"),
+            Err(DecodeErr::InvalidCharacter { position: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_non_canonical_strings() {
+        let hand = Suit::from_mpsz("123m406p555789s11z").unwrap();
+        let text = Suit::to_compact_string(&hand).unwrap();
+        assert_eq!(Suit::from_compact_string(&text), Ok(hand));
+
+        // 7 bytes leave four padding bits in the last character
+        let last = value_of(*text.as_bytes().last().unwrap()).unwrap();
+        for padding in 1..16 {
+            let mut other = text[..text.len() - 1].to_string();
+            other.push(Alphabet::UrlSafe.encode(last | padding) as char);
+            assert_eq!(
+                Suit::from_compact_string(&other),
+                Err(DecodeErr::InvalidPacking)
+            );
+        }
+
+        for extra in ["A", "AA", "AAA"] {
+            assert_eq!(
+                Suit::from_compact_string(&format!("{text}{extra}")),
+                Err(DecodeErr::InvalidPacking)
+            );
+        }
+    }
+}
@@ -0,0 +1,349 @@
+use crate::*;
+
+/// How many different tiles there are, ignoring red fives
+pub const TILE_KINDS: usize = 34;
+
+impl Suit {
+    /// A stable index for each kind of tile, `0..34`. 萬子 _(manzu)_ come first, then
+    /// 餅子 _(pinzu)_, 索子 _(so-zu)_, [Wind] and [Dragon], following the order of
+    /// [Ord for Suit](#impl-Ord-for-Suit). Red fives share the index of the plain five,
+    /// tiles that aren't [valid](Suit::is_valid) have no index.
+    ///
+    /// ```
+    /// # use mahjong_encoding::*;
+    /// assert_eq!(Suit::Characters(1).index(), Some(0));
+    /// assert_eq!(Suit::Dots(RED_FIVE).index(), Some(13));
+    /// assert_eq!(Suit::Dragon(Dragon::Red).index(), Some(33));
+    /// assert_eq!(Suit::Dots(0).index(), None);
+    /// ```
+    pub const fn index(&self) -> Option<usize> {
+        if !self.is_valid() {
+            return None;
+        }
+
+        Some(match self.normalize() {
+            Suit::Characters(n) => n as usize - 1,
+            Suit::Dots(n) => n as usize + 8,
+            Suit::Bamboo(n) => n as usize + 17,
+            Suit::Wind(wind) => wind as usize + 27,
+            Suit::Dragon(dragon) => dragon as usize + 31,
+        })
+    }
+
+    /// The tile with a given [index](Suit::index), never a red five
+    ///
+    /// ```
+    /// # use mahjong_encoding::*;
+    /// assert_eq!(Suit::from_index(27), Some(Suit::Wind(Wind::East)));
+    /// assert_eq!(Suit::from_index(34), None);
+    /// ```
+    pub const fn from_index(index: usize) -> Option<Suit> {
+        const WINDS: [Wind; 4] = [Wind::East, Wind::South, Wind::West, Wind::North];
+        const DRAGONS: [Dragon; 3] = [Dragon::White, Dragon::Green, Dragon::Red];
+
+        Some(match index {
+            0..=8 => Suit::Characters(index as u8 + 1),
+            9..=17 => Suit::Dots(index as u8 - 8),
+            18..=26 => Suit::Bamboo(index as u8 - 17),
+            27..=30 => Suit::Wind(WINDS[index - 27]),
+            31..=33 => Suit::Dragon(DRAGONS[index - 31]),
+            _ => return None,
+        })
+    }
+}
+
+/// A hand as a histogram of how many copies of each tile it holds, indexed by
+/// [Suit::index]. Red fives are counted with the plain fives and tracked on the side.
+///
+/// ```rust
+/// # use mahjong_encoding::*;
+/// let hand = Suit::from_string("yz0123UVWXXklm").unwrap();
+/// let counts = TileCounts::try_from(hand.as_slice()).unwrap();
+///
+/// assert_eq!(counts.get(Suit::Dots(7)), 2);
+/// assert_eq!(counts.len(), 14);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct TileCounts {
+    counts: [u8; TILE_KINDS],
+    red: [u8; 3],
+}
+
+impl Default for TileCounts {
+    fn default() -> TileCounts {
+        TileCounts {
+            counts: [0; TILE_KINDS],
+            red: [0; 3],
+        }
+    }
+}
+
+impl TileCounts {
+    /// An empty hand
+    pub fn new() -> TileCounts {
+        TileCounts::default()
+    }
+
+    /// Wraps a raw histogram, indexed by [Suit::index], without any red fives. Can
+    /// throw a [DecodeErr] if a tile appears more than four times
+    ///
+    /// ```
+    /// # use mahjong_encoding::*;
+    /// let mut counts = [0; TILE_KINDS];
+    /// counts[33] = 3;
+    /// assert_eq!(TileCounts::from_counts(counts).unwrap().len(), 3);
+    /// ```
+    pub fn from_counts(counts: [u8; TILE_KINDS]) -> Result<TileCounts, DecodeErr> {
+        for (index, count) in counts.iter().enumerate() {
+            if *count > 4 {
+                return Err(DecodeErr::TooManyCopies {
+                    tile: Suit::from_index(index).unwrap(),
+                    count: *count as usize,
+                });
+            }
+        }
+
+        Ok(TileCounts {
+            counts,
+            red: [0; 3],
+        })
+    }
+
+    /// Counts the tiles of a hand. Can throw a [DecodeErr] if a tile isn't valid or
+    /// appears more than four times
+    pub fn from_tiles(hand: &[Suit]) -> Result<TileCounts, DecodeErr> {
+        let mut counts = TileCounts::new();
+
+        for tile in hand {
+            let index = tile.index().ok_or(DecodeErr::InvalidTile { tile: *tile })?;
+
+            if counts.counts[index] == 4 {
+                return Err(DecodeErr::TooManyCopies {
+                    tile: tile.normalize(),
+                    count: hand.iter().filter(|other| other.is_same_tile(tile)).count(),
+                });
+            }
+
+            counts.counts[index] += 1;
+            if tile.is_red() {
+                counts.red[index / 9] += 1;
+            }
+        }
+
+        Ok(counts)
+    }
+
+    /// The raw histogram, indexed by [Suit::index]
+    pub fn counts(&self) -> &[u8; TILE_KINDS] {
+        &self.counts
+    }
+
+    /// How many copies of `tile` there are, red fives count as fives
+    pub fn get(&self, tile: Suit) -> u8 {
+        tile.index().map_or(0, |index| self.counts[index])
+    }
+
+    /// How many red fives there are, in 萬子 _(manzu)_, 餅子 _(pinzu)_, 索子 _(so-zu)_ order
+    pub fn red_fives(&self) -> [u8; 3] {
+        self.red
+    }
+
+    /// Total number of tiles
+    pub fn len(&self) -> usize {
+        self.counts.iter().map(|count| *count as usize).sum()
+    }
+
+    /// Whether there are no tiles at all
+    pub fn is_empty(&self) -> bool {
+        self.counts.iter().all(|count| *count == 0)
+    }
+
+    /// Adds a tile, returns `false` and leaves the counts untouched if that would
+    /// make a fifth copy or the tile isn't valid
+    pub fn add(&mut self, tile: Suit) -> bool {
+        match tile.index() {
+            Some(index) if self.counts[index] < 4 => {
+                self.counts[index] += 1;
+                if tile.is_red() {
+                    self.red[index / 9] += 1;
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Removes a tile, returns `false` and leaves the counts untouched if it isn't
+    /// there. Removing a plain five takes a red one only when no plain five is left.
+    pub fn remove(&mut self, tile: Suit) -> bool {
+        let Some(index) = tile.index() else {
+            return false;
+        };
+
+        if self.counts[index] == 0 {
+            return false;
+        }
+
+        let is_five = index < 27 && index % 9 == 4;
+        if tile.is_red() || is_five && self.counts[index] == self.red[index / 9] {
+            if self.red[index / 9] == 0 {
+                return false;
+            }
+            self.red[index / 9] -= 1;
+        }
+
+        self.counts[index] -= 1;
+        true
+    }
+
+    /// Removes every tile of a meld, returns `false` and leaves the counts untouched
+    /// if any of them isn't there
+    ///
+    /// ```
+    /// # use mahjong_encoding::*;
+    /// let hand = Suit::from_mpsz("123406m").unwrap();
+    /// let mut counts = TileCounts::try_from(hand.as_slice()).unwrap();
+    ///
+    /// assert!(counts.remove_meld(&Suit::from_mpsz("456m").unwrap()));
+    /// assert!(!counts.remove_meld(&Suit::from_mpsz("345m").unwrap()));
+    /// assert_eq!(counts.len(), 3);
+    /// ```
+    pub fn remove_meld(&mut self, meld: &[Suit]) -> bool {
+        let mut counts = *self;
+
+        if meld.iter().all(|tile| counts.remove(*tile)) {
+            *self = counts;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Lists the tiles in canonical order, see [Suit::sort_hand]
+    pub fn to_tiles(&self) -> Vec<Suit> {
+        let mut hand = vec![];
+
+        for (index, count) in self.counts.iter().enumerate() {
+            let tile = Suit::from_index(index).unwrap();
+            let red = if index < 27 && index % 9 == 4 {
+                self.red[index / 9].min(*count)
+            } else {
+                0
+            };
+
+            hand.extend((red..*count).map(|_| tile));
+            hand.extend((0..red).map(|_| tile.to_red()));
+        }
+
+        hand
+    }
+}
+
+impl TryFrom<&[Suit]> for TileCounts {
+    type Error = DecodeErr;
+
+    fn try_from(hand: &[Suit]) -> Result<TileCounts, DecodeErr> {
+        TileCounts::from_tiles(hand)
+    }
+}
+
+impl From<&TileCounts> for Vec<Suit> {
+    fn from(counts: &TileCounts) -> Vec<Suit> {
+        counts.to_tiles()
+    }
+}
+
+impl Suit {
+    pub(crate) const fn to_red(self) -> Suit {
+        match self {
+            Suit::Dots(5) => Suit::Dots(RED_FIVE),
+            Suit::Bamboo(5) => Suit::Bamboo(RED_FIVE),
+            Suit::Characters(5) => Suit::Characters(RED_FIVE),
+            tile => tile,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn every_tile_has_a_unique_index() {
+        for index in 0..TILE_KINDS {
+            let tile = Suit::from_index(index).unwrap();
+
+            assert_eq!(tile.index(), Some(index));
+        }
+
+        let mut tiles = (0..TILE_KINDS)
+            .filter_map(Suit::from_index)
+            .collect::<Vec<_>>();
+        let sorted = tiles.clone();
+        Suit::sort_hand(&mut tiles);
+        assert_eq!(tiles, sorted);
+    }
+
+    #[test]
+    fn round_trips_decoded_hands() {
+        let hand = Suit::from_mpsz("1550m406p789s1177z").unwrap();
+        let counts = TileCounts::try_from(hand.as_slice()).unwrap();
+
+        assert_eq!(counts.red_fives(), [1, 1, 0]);
+        assert_eq!(counts.get(Suit::Characters(5)), 3);
+        assert_eq!(Suit::to_mpsz(&counts.to_tiles()), "1550m406p789s1177z");
+    }
+
+    #[test]
+    fn rejects_fifth_copy() {
+        let hand = Suit::from_mpsz("22222z").unwrap();
+
+        assert_eq!(
+            TileCounts::try_from(hand.as_slice()),
+            Err(DecodeErr::TooManyCopies {
+                tile: Suit::Wind(Wind::South),
+                count: 5,
+            })
+        );
+
+        let mut counts = TileCounts::try_from(&hand[..4]).unwrap();
+        assert!(!counts.add(Suit::Wind(Wind::South)));
+        assert_eq!(counts.len(), 4);
+    }
+
+    #[test]
+    fn rejects_raw_counts_above_four() {
+        let mut counts = [0; TILE_KINDS];
+        counts[9] = 5;
+
+        assert_eq!(
+            TileCounts::from_counts(counts),
+            Err(DecodeErr::TooManyCopies {
+                tile: Suit::Dots(1),
+                count: 5,
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_tiles() {
+        assert_eq!(
+            TileCounts::from_tiles(&[Suit::Bamboo(0)]),
+            Err(DecodeErr::InvalidTile {
+                tile: Suit::Bamboo(0)
+            })
+        );
+    }
+
+    #[test]
+    fn removes_plain_fives_before_red_ones() {
+        let hand = Suit::from_mpsz("05p").unwrap();
+        let mut counts = TileCounts::try_from(hand.as_slice()).unwrap();
+
+        assert!(counts.remove(Suit::Dots(5)));
+        assert_eq!(counts.red_fives(), [0, 1, 0]);
+        assert!(counts.remove(Suit::Dots(5)));
+        assert_eq!(counts.red_fives(), [0, 0, 0]);
+        assert!(counts.is_empty());
+    }
+}
@@ -0,0 +1,357 @@
+use crate::*;
+
+/// 面子 _(mentsu)_,
+/// a group of three tiles inside a complete hand
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Group {
+    /// 順子 _(shuntsu)_, three consecutive tiles starting with the one given
+    Sequence(Suit),
+    /// 刻子 _(koutsu)_, three copies of the tile given
+    Triplet(Suit),
+}
+
+impl Group {
+    /// The tiles that make up this group
+    pub fn tiles(&self) -> [Suit; 3] {
+        match *self {
+            Group::Sequence(tile) => {
+                let next = tile.index().and_then(|index| Suit::from_index(index + 1));
+                let last = tile.index().and_then(|index| Suit::from_index(index + 2));
+                [tile, next.unwrap_or(tile), last.unwrap_or(tile)]
+            }
+            Group::Triplet(tile) => [tile; 3],
+        }
+    }
+
+    /// Whether `tile` is part of this group, red fives count as fives
+    pub fn contains(&self, tile: Suit) -> bool {
+        self.tiles().iter().any(|other| other.is_same_tile(&tile))
+    }
+}
+
+/// 待ち _(machi)_,
+/// the shape the winning tile completed
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Wait {
+    /// 両面 _(ryanmen)_, open wait on either side of two consecutive tiles
+    Ryanmen,
+    /// 嵌張 _(kanchan)_, closed wait in the middle of a sequence
+    Kanchan,
+    /// 辺張 _(penchan)_, edge wait on a 3 or a 7
+    Penchan,
+    /// 双碰 _(shanpon)_, one of two pairs becomes a triplet
+    Shanpon,
+    /// 単騎 _(tanki)_, single wait on the pair
+    Tanki,
+    /// 十三面 _(juusanmen)_, thirteen sided wait of 国士無双 _(kokushi musou)_
+    ThirteenSided,
+}
+
+/// How the tiles of a complete hand are arranged
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Shape {
+    /// Four melds and a pair, called melds aren't included in `groups`
+    Standard {
+        /// 雀頭 _(jantou)_
+        pair: Suit,
+        /// The concealed groups, in canonical order
+        groups: Vec<Group>,
+    },
+    /// 七対子 _(chiitoitsu)_, in canonical order
+    SevenPairs([Suit; 7]),
+    /// 国士無双 _(kokushi musou)_
+    ThirteenOrphans {
+        /// The terminal or honour that appears twice
+        pair: Suit,
+    },
+}
+
+/// One way of reading a complete hand, along with the tile it was won on and the
+/// wait that tile completed
+///
+/// ```rust
+/// # use mahjong_encoding::*;
+/// let hand = Suit::from_mpsz("111222333m789p11z").unwrap();
+/// let decompositions = Decomposition::of(&hand, Suit::Characters(3)).unwrap();
+///
+/// // three triplets won on a shanpon, or three identical sequences won on a penchan
+/// assert_eq!(decompositions.count(), 2);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Decomposition {
+    /// How the tiles are arranged
+    pub shape: Shape,
+    /// The tile the hand was won on
+    pub winning_tile: Suit,
+    /// The wait the winning tile completed
+    pub wait: Wait,
+}
+
+/// An iterator over every [Decomposition] of a hand, see [Decomposition::of]
+#[derive(Debug, Clone)]
+pub struct Decompositions(std::vec::IntoIter<Decomposition>);
+
+impl Iterator for Decompositions {
+    type Item = Decomposition;
+
+    fn next(&mut self) -> Option<Decomposition> {
+        self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl Decomposition {
+    /// Finds every way the concealed tiles of a complete hand, including the winning
+    /// tile, can be split into groups and a pair. Called melds are inferred from how
+    /// many tiles are left, so a hand with one called meld should have 11 tiles.
+    ///
+    /// Yields nothing if the hand isn't complete or doesn't hold the winning tile.
+    /// Can throw a [DecodeErr] if the hand isn't a valid length
+    pub fn of(hand: &[Suit], winning_tile: Suit) -> Result<Decompositions, DecodeErr> {
+        let counts = TileCounts::from_tiles(hand)?;
+
+        let length = counts.len();
+        if length > 14 || length % 3 != 2 {
+            return Err(DecodeErr::InvalidHandLength { length });
+        }
+
+        let mut found = vec![];
+        if counts.get(winning_tile) == 0 {
+            return Ok(Decompositions(found.into_iter()));
+        }
+
+        let mut push = |shape: Shape, wait: Wait| {
+            let decomposition = Decomposition {
+                shape,
+                winning_tile,
+                wait,
+            };
+            if !found.contains(&decomposition) {
+                found.push(decomposition);
+            }
+        };
+
+        for (pair, groups) in standard(counts.counts()) {
+            if pair.is_same_tile(&winning_tile) {
+                push(
+                    Shape::Standard {
+                        pair,
+                        groups: groups.clone(),
+                    },
+                    Wait::Tanki,
+                );
+            }
+
+            for group in groups.iter().filter(|group| group.contains(winning_tile)) {
+                push(
+                    Shape::Standard {
+                        pair,
+                        groups: groups.clone(),
+                    },
+                    group.wait_on(winning_tile),
+                );
+            }
+        }
+
+        if length == 14 {
+            if let Some(pairs) = seven_pairs(counts.counts()) {
+                push(Shape::SevenPairs(pairs), Wait::Tanki);
+            }
+
+            if let Some(pair) = thirteen_orphans(counts.counts()) {
+                let wait = if pair.is_same_tile(&winning_tile) {
+                    Wait::ThirteenSided
+                } else {
+                    Wait::Tanki
+                };
+                push(Shape::ThirteenOrphans { pair }, wait);
+            }
+        }
+
+        Ok(Decompositions(found.into_iter()))
+    }
+}
+
+impl Group {
+    fn wait_on(&self, tile: Suit) -> Wait {
+        match self {
+            Group::Triplet(_) => Wait::Shanpon,
+            Group::Sequence(first) => {
+                let [_, middle, last] = self.tiles();
+                let rank = |tile: Suit| tile.index().map_or(0, |index| index % 9 + 1);
+
+                if middle.is_same_tile(&tile) {
+                    Wait::Kanchan
+                } else if last.is_same_tile(&tile) && rank(*first) == 1
+                    || first.is_same_tile(&tile) && rank(last) == 9
+                {
+                    Wait::Penchan
+                } else {
+                    Wait::Ryanmen
+                }
+            }
+        }
+    }
+}
+
+/// Every pair and set of groups that use up all of `counts`
+fn standard(counts: &[u8; TILE_KINDS]) -> Vec<(Suit, Vec<Group>)> {
+    let mut found = vec![];
+
+    for index in 0..TILE_KINDS {
+        if counts[index] < 2 {
+            continue;
+        }
+
+        let mut rest = *counts;
+        rest[index] -= 2;
+
+        let pair = Suit::from_index(index).unwrap();
+        let mut groups = vec![];
+        split(&mut rest, 0, &mut groups, &mut |groups| {
+            found.push((pair, groups.to_vec()))
+        });
+    }
+
+    found
+}
+
+fn split(
+    counts: &mut [u8; TILE_KINDS],
+    start: usize,
+    groups: &mut Vec<Group>,
+    found: &mut impl FnMut(&[Group]),
+) {
+    let Some(i) = (start..TILE_KINDS).find(|i| counts[*i] > 0) else {
+        found(groups);
+        return;
+    };
+
+    let tile = Suit::from_index(i).unwrap();
+
+    if counts[i] >= 3 {
+        counts[i] -= 3;
+        groups.push(Group::Triplet(tile));
+        split(counts, i, groups, found);
+        groups.pop();
+        counts[i] += 3;
+    }
+
+    if i < 27 && i % 9 <= 6 && counts[i + 1] > 0 && counts[i + 2] > 0 {
+        [i, i + 1, i + 2].iter().for_each(|tile| counts[*tile] -= 1);
+        groups.push(Group::Sequence(tile));
+        split(counts, i, groups, found);
+        groups.pop();
+        [i, i + 1, i + 2].iter().for_each(|tile| counts[*tile] += 1);
+    }
+}
+
+fn seven_pairs(counts: &[u8; TILE_KINDS]) -> Option<[Suit; 7]> {
+    let pairs = (0..TILE_KINDS)
+        .filter(|index| counts[*index] == 2)
+        .filter_map(Suit::from_index)
+        .collect::<Vec<_>>();
+
+    pairs.try_into().ok()
+}
+
+fn thirteen_orphans(counts: &[u8; TILE_KINDS]) -> Option<Suit> {
+    const TERMINALS_AND_HONOURS: [usize; 13] = [0, 8, 9, 17, 18, 26, 27, 28, 29, 30, 31, 32, 33];
+
+    if TERMINALS_AND_HONOURS
+        .iter()
+        .any(|index| counts[*index] == 0)
+    {
+        return None;
+    }
+
+    TERMINALS_AND_HONOURS
+        .iter()
+        .find(|index| counts[**index] == 2)
+        .and_then(|index| Suit::from_index(*index))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn decompose(hand: &str, winning_tile: &str) -> Vec<Decomposition> {
+        let hand = Suit::from_mpsz(hand).unwrap();
+        let winning_tile = Suit::from_mpsz(winning_tile).unwrap()[0];
+
+        Decomposition::of(&hand, winning_tile).unwrap().collect()
+    }
+
+    fn waits(hand: &str, winning_tile: &str) -> Vec<Wait> {
+        decompose(hand, winning_tile)
+            .into_iter()
+            .map(|decomposition| decomposition.wait)
+            .collect()
+    }
+
+    #[test]
+    fn finds_every_wait_shape() {
+        assert_eq!(waits("234m456p789s11z567m", "2m"), [Wait::Ryanmen]);
+        assert_eq!(waits("234m456p789s11z567m", "3m"), [Wait::Kanchan]);
+        assert_eq!(waits("123m456p789s11z567m", "3m"), [Wait::Penchan]);
+        assert_eq!(waits("789m456p789s11z123m", "7m"), [Wait::Penchan]);
+        assert_eq!(waits("111m456p789s22z345m", "1m"), [Wait::Shanpon]);
+        assert_eq!(waits("123m456p789s22z345m", "2z"), [Wait::Tanki]);
+    }
+
+    #[test]
+    fn finds_every_reading_of_a_hand() {
+        let found = decompose("111222333m789p11z", "1m");
+
+        assert_eq!(found.len(), 2);
+        assert!(found.contains(&Decomposition {
+            shape: Shape::Standard {
+                pair: Suit::Wind(Wind::East),
+                groups: vec![
+                    Group::Triplet(Suit::Characters(1)),
+                    Group::Triplet(Suit::Characters(2)),
+                    Group::Triplet(Suit::Characters(3)),
+                    Group::Sequence(Suit::Dots(7)),
+                ],
+            },
+            winning_tile: Suit::Characters(1),
+            wait: Wait::Shanpon,
+        }));
+    }
+
+    #[test]
+    fn finds_seven_pairs_alongside_standard_shapes() {
+        let found = decompose("112233m445566p77z", "7z");
+
+        assert!(found
+            .iter()
+            .any(|decomposition| matches!(decomposition.shape, Shape::SevenPairs(_))));
+        assert!(found
+            .iter()
+            .any(|decomposition| matches!(decomposition.shape, Shape::Standard { .. })));
+    }
+
+    #[test]
+    fn finds_thirteen_orphans() {
+        assert_eq!(waits("19m19p19s12345677z", "7z"), [Wait::ThirteenSided]);
+        assert_eq!(waits("19m19p19s12345677z", "1z"), [Wait::Tanki]);
+    }
+
+    #[test]
+    fn supports_called_melds_and_red_fives() {
+        let found = decompose("340m11z", "0m");
+
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].wait, Wait::Ryanmen);
+        assert_eq!(found[0].winning_tile, Suit::Characters(RED_FIVE));
+    }
+
+    #[test]
+    fn yields_nothing_for_incomplete_hands() {
+        assert!(decompose("123m456p789s12z345m", "1z").is_empty());
+        assert!(decompose("123m456p789s11z345m", "9m").is_empty());
+    }
+}
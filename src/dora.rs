@@ -0,0 +1,185 @@
+use crate::*;
+
+impl Wind {
+    /// The next wind in seat order, east → south → west → north → east
+    ///
+    /// ```
+    /// # use mahjong_encoding::*;
+    /// assert_eq!(Wind::East.successor(), Wind::South);
+    /// assert_eq!(Wind::North.successor(), Wind::East);
+    /// ```
+    pub const fn successor(self) -> Wind {
+        match self {
+            Wind::East => Wind::South,
+            Wind::South => Wind::West,
+            Wind::West => Wind::North,
+            Wind::North => Wind::East,
+        }
+    }
+
+    /// The previous wind in seat order, the inverse of [Wind::successor]
+    pub const fn predecessor(self) -> Wind {
+        match self {
+            Wind::East => Wind::North,
+            Wind::South => Wind::East,
+            Wind::West => Wind::South,
+            Wind::North => Wind::West,
+        }
+    }
+}
+
+impl Dragon {
+    /// The next dragon in dora order, white → green → red → white
+    ///
+    /// ```
+    /// # use mahjong_encoding::*;
+    /// assert_eq!(Dragon::White.successor(), Dragon::Green);
+    /// assert_eq!(Dragon::Red.successor(), Dragon::White);
+    /// ```
+    pub const fn successor(self) -> Dragon {
+        match self {
+            Dragon::White => Dragon::Green,
+            Dragon::Green => Dragon::Red,
+            Dragon::Red => Dragon::White,
+        }
+    }
+
+    /// The previous dragon in dora order, the inverse of [Dragon::successor]
+    pub const fn predecessor(self) -> Dragon {
+        match self {
+            Dragon::White => Dragon::Red,
+            Dragon::Green => Dragon::White,
+            Dragon::Red => Dragon::Green,
+        }
+    }
+}
+
+impl Suit {
+    /// The tile a ドラ表示牌 _(dora hyoujihai)_ indicates. Numbers wrap from 9 to 1,
+    /// [Wind] and [Dragon] cycle, see [Wind::successor] and [Dragon::successor].
+    /// A red five indicates a six, and nothing is ever followed by a red five.
+    /// Tiles that aren't [valid](Suit::is_valid) have no successor.
+    ///
+    /// ```
+    /// # use mahjong_encoding::*;
+    /// assert_eq!(Suit::Dots(9).successor(), Some(Suit::Dots(1)));
+    /// assert_eq!(Suit::Bamboo(RED_FIVE).successor(), Some(Suit::Bamboo(6)));
+    /// assert_eq!(Suit::Wind(Wind::North).successor(), Some(Suit::Wind(Wind::East)));
+    /// assert_eq!(Suit::Dots(0).successor(), None);
+    /// ```
+    pub const fn successor(self) -> Option<Suit> {
+        if !self.is_valid() {
+            return None;
+        }
+
+        Some(match self.normalize() {
+            Suit::Dots(n) => Suit::Dots(n % 9 + 1),
+            Suit::Bamboo(n) => Suit::Bamboo(n % 9 + 1),
+            Suit::Characters(n) => Suit::Characters(n % 9 + 1),
+            Suit::Wind(wind) => Suit::Wind(wind.successor()),
+            Suit::Dragon(dragon) => Suit::Dragon(dragon.successor()),
+        })
+    }
+
+    /// The tile that would indicate this one as ドラ _(dora)_, the inverse of
+    /// [Suit::successor]. Red fives are treated as plain fives.
+    ///
+    /// ```
+    /// # use mahjong_encoding::*;
+    /// assert_eq!(Suit::Characters(1).predecessor(), Some(Suit::Characters(9)));
+    /// assert_eq!(Suit::Dragon(Dragon::White).predecessor(), Some(Suit::Dragon(Dragon::Red)));
+    /// ```
+    pub const fn predecessor(self) -> Option<Suit> {
+        if !self.is_valid() {
+            return None;
+        }
+
+        Some(match self.normalize() {
+            Suit::Dots(n) => Suit::Dots((n + 7) % 9 + 1),
+            Suit::Bamboo(n) => Suit::Bamboo((n + 7) % 9 + 1),
+            Suit::Characters(n) => Suit::Characters((n + 7) % 9 + 1),
+            Suit::Wind(wind) => Suit::Wind(wind.predecessor()),
+            Suit::Dragon(dragon) => Suit::Dragon(dragon.predecessor()),
+        })
+    }
+
+    /// Counts the ドラ _(dora)_ in a hand, one for every indicator each tile matches. A
+    /// [RED_FIVE] matches like any other five, the 赤ドラ _(aka dora)_ it is worth on
+    /// top is left to [Suit::count_red], the same split as [Win::dora] and
+    /// [Score::aka_dora]. Can throw a [DecodeErr] if an indicator isn't a tile
+    ///
+    /// ```
+    /// # use mahjong_encoding::*;
+    /// let hand = Suit::from_mpsz("123m406p789s1122z").unwrap();
+    /// let indicators = Suit::from_mpsz("9m4z4p").unwrap();
+    ///
+    /// // 1m, two 1z and the red 5p
+    /// assert_eq!(Suit::count_dora(&hand, &indicators), Ok(4));
+    /// assert_eq!(Suit::count_red(&hand), 1);
+    /// ```
+    pub fn count_dora(hand: &[Suit], indicators: &[Suit]) -> Result<usize, DecodeErr> {
+        let mut dora = 0;
+
+        for indicator in indicators {
+            let tile = indicator
+                .successor()
+                .ok_or(DecodeErr::InvalidTile { tile: *indicator })?;
+            dora += hand
+                .iter()
+                .filter(|other| other.is_same_tile(&tile))
+                .count();
+        }
+
+        Ok(dora)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn successor_and_predecessor_are_inverse() {
+        for tile in (0..TILE_KINDS).filter_map(Suit::from_index) {
+            let next = tile.successor().unwrap();
+
+            assert_ne!(next, tile);
+            assert_eq!(next.predecessor(), Some(tile));
+            assert_eq!(tile.predecessor().unwrap().successor(), Some(tile));
+        }
+    }
+
+    #[test]
+    fn cycles_stay_within_their_suit() {
+        let mut tile = Suit::Bamboo(1);
+        for _ in 0..9 {
+            tile = tile.successor().unwrap();
+            assert!(matches!(tile, Suit::Bamboo(_)));
+        }
+        assert_eq!(tile, Suit::Bamboo(1));
+
+        let mut dragon = Dragon::White;
+        for _ in 0..3 {
+            dragon = dragon.successor();
+        }
+        assert_eq!(dragon, Dragon::White);
+    }
+
+    #[test]
+    fn counts_every_indicator_separately() {
+        let hand = Suit::from_mpsz("555m0p").unwrap();
+
+        assert_eq!(Suit::count_dora(&hand, &[]), Ok(0));
+        assert_eq!(Suit::count_dora(&hand, &[Suit::Characters(4)]), Ok(3));
+        assert_eq!(
+            Suit::count_dora(&hand, &[Suit::Characters(4), Suit::Dots(4)]),
+            Ok(4)
+        );
+        assert_eq!(
+            Suit::count_dora(&hand, &[Suit::Dots(0)]),
+            Err(DecodeErr::InvalidTile {
+                tile: Suit::Dots(0)
+            })
+        );
+    }
+}
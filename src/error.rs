@@ -0,0 +1,228 @@
+use std::fmt;
+
+use crate::*;
+
+/// Errors that can be thrown when converting from [&str] -> [`Vec<Suit>`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DecodeErr {
+    /// The character you used does not refer to a tile
+    InvalidCharacter {
+        /// The offending byte of the input
+        byte: u8,
+        /// Byte offset into the input
+        position: usize,
+        /// Human readable list of what would have been accepted here
+        expected: &'static str,
+    },
+    /// The rank at `position` is not followed by a suit letter
+    MissingSuit {
+        /// Byte offset into the input
+        position: usize,
+    },
+    /// The suit letter at `position` has no ranks in front of it
+    MissingRank {
+        /// Byte offset into the input
+        position: usize,
+    },
+    /// The honour at `position` is not one of `1z`–`7z`
+    InvalidHonour {
+        /// Byte offset into the input
+        position: usize,
+    },
+    /// `tile` has a rank that doesn't exist, see [Suit::is_valid]
+    InvalidTile {
+        /// The offending tile
+        tile: Suit,
+    },
+    /// There are more than four copies of `tile`
+    TooManyCopies {
+        /// The tile that appears too often
+        tile: Suit,
+        /// How many times it appears
+        count: usize,
+    },
+    /// A hand cannot be made up of `length` tiles
+    InvalidHandLength {
+        /// How many tiles were found
+        length: usize,
+    },
+    /// The tiles don't make up a meld of this kind, or it can't have been claimed
+    /// the way it says
+    InvalidMeld {
+        /// The kind of meld that was being made
+        kind: MeldKind,
+    },
+    /// The input stopped part way through a meld or a marked tile
+    UnexpectedEnd {
+        /// Byte offset into the input
+        position: usize,
+    },
+    /// A hand with open melds can't be in riichi
+    InvalidRiichi,
+    /// The same flower appears more than once
+    DuplicateFlower {
+        /// The flower that appears twice
+        flower: Flower,
+    },
+    /// The check characters at the end of the input don't match the rest of it
+    ChecksumMismatch {
+        /// Byte offset of the character that was most likely mistyped, if one
+        /// mistyped character would explain the mismatch
+        likely_position: Option<usize>,
+    },
+    /// Packed bytes don't describe a hand, see [TileCounts::to_compact]
+    InvalidPacking,
+    /// The input was written by a format version this crate doesn't know about
+    UnknownVersion {
+        /// The version found in the input
+        version: u8,
+    },
+    /// A field of an imported log is missing or holds something that can't be read
+    InvalidField {
+        /// The name of the field
+        field: &'static str,
+    },
+    /// A number in an imported log doesn't stand for any tile
+    InvalidTileCode {
+        /// The number found
+        code: u32,
+    },
+}
+
+impl fmt::Display for DecodeErr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeErr::InvalidCharacter {
+                byte,
+                position,
+                expected,
+            } => {
+                if byte.is_ascii_graphic() {
+                    write!(f, "invalid character '{}'", *byte as char)?;
+                } else {
+                    write!(f, "invalid byte 0x{byte:02x}")?;
+                }
+                write!(f, " at position {position}, expected {expected}")
+            }
+            DecodeErr::MissingSuit { position } => {
+                write!(f, "rank at position {position} is not followed by a suit")
+            }
+            DecodeErr::MissingRank { position } => {
+                write!(f, "suit at position {position} has no ranks in front of it")
+            }
+            DecodeErr::InvalidHonour { position } => {
+                write!(f, "honour at position {position} must be one of 1z-7z")
+            }
+            DecodeErr::InvalidTile { tile } => write!(f, "{tile:?} is not a valid tile"),
+            DecodeErr::TooManyCopies { tile, count } => {
+                write!(f, "found {count} copies of {tile:?}, at most 4 are allowed")
+            }
+            DecodeErr::InvalidHandLength { length } => {
+                write!(f, "a hand cannot be made of {length} tiles")
+            }
+            DecodeErr::InvalidMeld { kind } => write!(f, "tiles don't make up a valid {kind:?}"),
+            DecodeErr::UnexpectedEnd { position } => {
+                write!(
+                    f,
+                    "input ended at position {position}, part way through a meld"
+                )
+            }
+            DecodeErr::InvalidRiichi => write!(f, "a hand with open melds can't be in riichi"),
+            DecodeErr::DuplicateFlower { flower } => {
+                write!(f, "{flower:?} appears more than once")
+            }
+            DecodeErr::ChecksumMismatch { likely_position } => {
+                write!(f, "checksum doesn't match")?;
+                match likely_position {
+                    Some(position) => write!(f, ", check the character at position {position}"),
+                    None => Ok(()),
+                }
+            }
+            DecodeErr::InvalidPacking => write!(f, "packed bytes don't describe a hand"),
+            DecodeErr::UnknownVersion { version } => {
+                write!(f, "unknown format version {version}")
+            }
+            DecodeErr::InvalidField { field } => {
+                write!(f, "missing or invalid field {field}")
+            }
+            DecodeErr::InvalidTileCode { code } => write!(f, "{code} isn't a tile code"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeErr {}
+
+impl DecodeErr {
+    /// Moves any position in the error along by `offset`, for errors found in part of
+    /// a larger input
+    pub(crate) fn offset(self, offset: usize) -> DecodeErr {
+        match self {
+            DecodeErr::InvalidCharacter {
+                byte,
+                position,
+                expected,
+            } => DecodeErr::InvalidCharacter {
+                byte,
+                position: position + offset,
+                expected,
+            },
+            DecodeErr::MissingSuit { position } => DecodeErr::MissingSuit {
+                position: position + offset,
+            },
+            DecodeErr::MissingRank { position } => DecodeErr::MissingRank {
+                position: position + offset,
+            },
+            DecodeErr::InvalidHonour { position } => DecodeErr::InvalidHonour {
+                position: position + offset,
+            },
+            DecodeErr::UnexpectedEnd { position } => DecodeErr::UnexpectedEnd {
+                position: position + offset,
+            },
+            DecodeErr::ChecksumMismatch { likely_position } => DecodeErr::ChecksumMismatch {
+                likely_position: likely_position.map(|position| position + offset),
+            },
+            err => err,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn displays_offending_character() {
+        let err = DecodeErr::InvalidCharacter {
+            byte: b'!',
+            position: 3,
+            expected: "a tile",
+        };
+
+        assert_eq!(
+            err.to_string(),
+            "invalid character '!' at position 3, expected a tile"
+        );
+    }
+
+    #[test]
+    fn displays_unprintable_bytes_as_hex() {
+        let err = DecodeErr::InvalidCharacter {
+            byte: 0x7f,
+            position: 0,
+            expected: "a tile",
+        };
+
+        assert_eq!(
+            err.to_string(),
+            "invalid byte 0x7f at position 0, expected a tile"
+        );
+    }
+
+    #[test]
+    fn is_a_std_error() {
+        let err: Box<dyn std::error::Error> = Box::new(DecodeErr::UnknownVersion { version: 9 });
+
+        assert_eq!(err.to_string(), "unknown format version 9");
+    }
+}
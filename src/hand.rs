@@ -0,0 +1,458 @@
+use std::fmt;
+
+use crate::*;
+
+/// Marks the tile that was just drawn, or the winning tile
+const DRAWN: u8 = 0x06;
+/// Marks a hand in 立直 _(riichi)_
+const RIICHI: u8 = 0x07;
+/// Marks a hand in ダブル立直 _(daburu riichi)_
+const DOUBLE_RIICHI: u8 = 0x08;
+
+/// Human readable description of what can appear in an encoded [Hand]
+const EXPECTED_HAND: &str = "a tile, a flower, or one of the markers B-I";
+/// Human readable description of what must follow the drawn tile marker
+const EXPECTED_TILE: &str = "a tile";
+/// Human readable description of what can follow once a hand's markers are used up
+const EXPECTED_UNUSED: &str = "a tile, a flower, or a marker that hasn't been used yet";
+
+/// 花牌 _(hanapai)_,
+/// bonus tiles that are set aside as soon as they are drawn
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Flower {
+    /// 梅 _(ume)_
+    Plum,
+    /// 蘭 _(ran)_
+    Orchid,
+    /// 菊 _(kiku)_
+    Chrysanthemum,
+    /// 竹 _(take)_
+    Bamboo,
+    /// 春 _(haru)_
+    Spring,
+    /// 夏 _(natsu)_
+    Summer,
+    /// 秋 _(aki)_
+    Autumn,
+    /// 冬 _(fuyu)_
+    Winter,
+}
+
+impl ToByte for Flower {
+    fn to_byte(&self) -> u8 {
+        match self {
+            Flower::Plum => 0x0E,
+            Flower::Orchid => 0x1E,
+            Flower::Chrysanthemum => 0x2E,
+            Flower::Bamboo => 0x3E,
+            Flower::Spring => 0x0F,
+            Flower::Summer => 0x1F,
+            Flower::Autumn => 0x2F,
+            Flower::Winter => 0x3F,
+        }
+    }
+}
+
+impl Flower {
+    pub(crate) const fn from_byte(byte: u8) -> Option<Flower> {
+        Some(match byte {
+            0x0E => Flower::Plum,
+            0x1E => Flower::Orchid,
+            0x2E => Flower::Chrysanthemum,
+            0x3E => Flower::Bamboo,
+            0x0F => Flower::Spring,
+            0x1F => Flower::Summer,
+            0x2F => Flower::Autumn,
+            0x3F => Flower::Winter,
+            _ => return None,
+        })
+    }
+}
+
+/// A player's hand part way through a round: the concealed tiles, called melds, the
+/// tile just drawn, riichi status and any flowers set aside
+///
+/// ```rust
+/// # use mahjong_encoding::*;
+/// let hand = Hand::new(
+///     Suit::from_mpsz("123m456p789s2m").unwrap(),
+///     vec![Meld::new(MeldKind::Pon, Suit::from_mpsz("777z").unwrap()).unwrap()],
+///     Some(Suit::Characters(2)),
+///     Riichi::None,
+///     vec![Flower::Plum],
+/// )
+/// .unwrap();
+///
+/// assert_eq!(Hand::from_string(&hand.to_string()), Ok(hand));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Hand {
+    concealed: Vec<Suit>,
+    melds: Vec<Meld>,
+    drawn: Option<Suit>,
+    riichi: Riichi,
+    flowers: Vec<Flower>,
+}
+
+impl Hand {
+    /// Puts a hand together, checking that it could exist. The concealed tiles and
+    /// melds must add up to 13 tiles, kans counting as three, with `drawn` as the
+    /// 14th. No tile can appear more than four times, a hand with open melds can't
+    /// be in riichi and each flower only exists once. Can throw a [DecodeErr]
+    pub fn new(
+        mut concealed: Vec<Suit>,
+        melds: Vec<Meld>,
+        drawn: Option<Suit>,
+        riichi: Riichi,
+        mut flowers: Vec<Flower>,
+    ) -> Result<Hand, DecodeErr> {
+        let length = concealed.len() + 3 * melds.len();
+        if length != 13 {
+            return Err(DecodeErr::InvalidHandLength { length });
+        }
+
+        let all = concealed
+            .iter()
+            .chain(drawn.iter())
+            .chain(melds.iter().flat_map(|meld| meld.tiles()))
+            .copied()
+            .collect::<Vec<_>>();
+        TileCounts::from_tiles(&all)?;
+
+        if riichi != Riichi::None && melds.iter().any(|meld| meld.is_open()) {
+            return Err(DecodeErr::InvalidRiichi);
+        }
+
+        flowers.sort_unstable();
+        if let Some(pair) = flowers.windows(2).find(|pair| pair[0] == pair[1]) {
+            return Err(DecodeErr::DuplicateFlower { flower: pair[0] });
+        }
+
+        Suit::sort_hand(&mut concealed);
+
+        Ok(Hand {
+            concealed,
+            melds,
+            drawn,
+            riichi,
+            flowers,
+        })
+    }
+
+    /// The concealed tiles, not including the drawn tile, in canonical order
+    pub fn concealed(&self) -> &[Suit] {
+        &self.concealed
+    }
+
+    /// Called melds and closed kans, in the order they were made
+    pub fn melds(&self) -> &[Meld] {
+        &self.melds
+    }
+
+    /// The tile that was just drawn or claimed to win, if any
+    pub fn drawn(&self) -> Option<Suit> {
+        self.drawn
+    }
+
+    /// Riichi status of the hand
+    pub fn riichi(&self) -> Riichi {
+        self.riichi
+    }
+
+    /// Flowers set aside, in canonical order
+    pub fn flowers(&self) -> &[Flower] {
+        &self.flowers
+    }
+
+    /// Whether the hand has no open melds
+    pub fn is_closed(&self) -> bool {
+        self.melds.iter().all(|meld| !meld.is_open())
+    }
+
+    /// The hand as a [Win] on the drawn tile, ready for scoring. `None` if there is no
+    /// drawn tile
+    pub fn win(&self, win_type: WinType, seat_wind: Wind, round_wind: Wind) -> Option<Win> {
+        let winning_tile = self.drawn?;
+        let mut concealed = self.concealed.clone();
+        concealed.push(winning_tile);
+
+        let mut win = Win::new(
+            concealed,
+            self.melds.clone(),
+            winning_tile,
+            win_type,
+            seat_wind,
+            round_wind,
+        );
+        win.riichi = self.riichi;
+
+        Some(win)
+    }
+
+    /// Converts from a plain text string into a hand, see
+    /// [Display for Hand](#impl-Display-for-Hand). A bare string of 13 tiles is a hand
+    /// without melds or a drawn tile. Can throw a [DecodeErr]
+    ///
+    /// ```
+    /// # use mahjong_encoding::*;
+    /// let hand = Hand::from_string("yz0123UVWXXkl").unwrap();
+    /// assert_eq!(hand.drawn(), None);
+    /// ```
+    pub fn from_string(input: &str) -> Result<Hand, DecodeErr> {
+        let input = input.as_bytes();
+        let mut position = 0;
+
+        let mut concealed = vec![];
+        let mut melds = vec![];
+        let mut drawn = None;
+        let mut riichi = Riichi::None;
+        let mut flowers = vec![];
+
+        while let Some(&byte) = input.get(position) {
+            let err = DecodeErr::InvalidCharacter {
+                byte,
+                position,
+                expected: EXPECTED_HAND,
+            };
+            let value = value_of(byte).ok_or(err)?;
+
+            if MeldKind::from_byte(value).is_some() {
+                melds.push(Meld::decode(input, &mut position)?);
+                continue;
+            }
+
+            let repeated = match value {
+                DRAWN => drawn.is_some(),
+                RIICHI | DOUBLE_RIICHI => riichi != Riichi::None,
+                _ => false,
+            };
+            if repeated {
+                return Err(DecodeErr::InvalidCharacter {
+                    byte,
+                    position,
+                    expected: EXPECTED_UNUSED,
+                });
+            }
+
+            position += 1;
+            if let Some(tile) = INDEX[byte as usize] {
+                concealed.push(tile);
+            } else if let Some(flower) = Flower::from_byte(value) {
+                flowers.push(flower);
+            } else if value == RIICHI {
+                riichi = Riichi::Riichi;
+            } else if value == DOUBLE_RIICHI {
+                riichi = Riichi::DoubleRiichi;
+            } else if value == DRAWN {
+                let &byte = input
+                    .get(position)
+                    .ok_or(DecodeErr::UnexpectedEnd { position })?;
+                drawn = Some(INDEX[byte as usize].ok_or(DecodeErr::InvalidCharacter {
+                    byte,
+                    position,
+                    expected: EXPECTED_TILE,
+                })?);
+                position += 1;
+            } else {
+                return Err(err);
+            }
+        }
+
+        Hand::new(concealed, melds, drawn, riichi, flowers)
+    }
+}
+
+/// Writes the hand as a single plain text string. The concealed tiles are written as
+/// in [Suit::to_string], followed by the melds as in [Meld::to_string], then `G` and
+/// the drawn tile, `H` for riichi or `I` for double riichi, and finally the flowers.
+impl fmt::Display for Hand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut out = Suit::to_string(&self.concealed).into_bytes();
+        self.melds.iter().for_each(|meld| meld.encode(&mut out));
+
+        if let Some(drawn) = self.drawn {
+            out.push(ALPHABET[DRAWN as usize]);
+            out.push(ALPHABET[drawn.to_byte() as usize]);
+        }
+
+        match self.riichi {
+            Riichi::None => {}
+            Riichi::Riichi => out.push(ALPHABET[RIICHI as usize]),
+            Riichi::DoubleRiichi => out.push(ALPHABET[DOUBLE_RIICHI as usize]),
+        }
+
+        out.extend(
+            self.flowers
+                .iter()
+                .map(|flower| ALPHABET[flower.to_byte() as usize]),
+        );
+
+        f.write_str(std::str::from_utf8(&out).unwrap())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn tiles(hand: &str) -> Vec<Suit> {
+        Suit::from_mpsz(hand).unwrap()
+    }
+
+    fn pon(tile: &str) -> Meld {
+        Meld::new(MeldKind::Pon, tiles(tile)).unwrap()
+    }
+
+    #[test]
+    fn checks_tile_count_against_melds() {
+        assert!(Hand::new(
+            tiles("123m456p789s"),
+            vec![pon("777z")],
+            None,
+            Riichi::None,
+            vec![]
+        )
+        .is_err());
+        assert_eq!(
+            Hand::new(
+                tiles("123m456p789s12z"),
+                vec![pon("777z")],
+                None,
+                Riichi::None,
+                vec![]
+            ),
+            Err(DecodeErr::InvalidHandLength { length: 14 })
+        );
+    }
+
+    #[test]
+    fn counts_copies_across_melds_and_the_drawn_tile() {
+        assert_eq!(
+            Hand::new(
+                tiles("7z123m456p789s"),
+                vec![pon("777z")],
+                Some(Suit::Dragon(Dragon::Red)),
+                Riichi::None,
+                vec![],
+            ),
+            Err(DecodeErr::TooManyCopies {
+                tile: Suit::Dragon(Dragon::Red),
+                count: 5,
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_open_riichi_and_duplicate_flowers() {
+        let concealed = tiles("123m456p789s1z");
+
+        assert_eq!(
+            Hand::new(
+                concealed.clone(),
+                vec![pon("777z")],
+                None,
+                Riichi::Riichi,
+                vec![]
+            ),
+            Err(DecodeErr::InvalidRiichi)
+        );
+        assert_eq!(
+            Hand::new(
+                concealed,
+                vec![pon("777z")],
+                None,
+                Riichi::None,
+                vec![Flower::Autumn, Flower::Plum, Flower::Autumn],
+            ),
+            Err(DecodeErr::DuplicateFlower {
+                flower: Flower::Autumn
+            })
+        );
+    }
+
+    #[test]
+    fn round_trips_every_part() {
+        let hand = Hand::new(
+            tiles("123m406p7899s"),
+            vec![Meld::new(MeldKind::ClosedKan, tiles("1111z")).unwrap()],
+            Some(Suit::Bamboo(9)),
+            Riichi::DoubleRiichi,
+            vec![Flower::Winter, Flower::Bamboo],
+        )
+        .unwrap();
+        let text = hand.to_string();
+
+        assert!(text.ends_with("I+/"));
+        assert_eq!(Hand::from_string(&text), Ok(hand));
+    }
+
+    #[test]
+    fn turns_into_a_win() {
+        let hand = Hand::from_string(&Suit::to_string(&tiles("234m456p678s2234s"))).unwrap();
+        assert!(hand.win(WinType::Tsumo, Wind::East, Wind::East).is_none());
+
+        let hand = Hand::new(
+            tiles("234m456p678s2234s"),
+            vec![],
+            Some(Suit::Bamboo(5)),
+            Riichi::Riichi,
+            vec![],
+        )
+        .unwrap();
+        let win = hand.win(WinType::Tsumo, Wind::East, Wind::East).unwrap();
+
+        assert_eq!(win.concealed.len(), 14);
+        assert!(win.yaku().unwrap().unwrap().yaku.contains(&Yaku::Riichi));
+    }
+
+    #[test]
+    fn reports_bad_characters() {
+        assert_eq!(
+            Hand::from_string("yz0123UVWXXk!"),
+            Err(DecodeErr::InvalidCharacter {
+                byte: b'!',
+                position: 12,
+                expected: EXPECTED_HAND,
+            })
+        );
+        assert_eq!(
+            Hand::from_string("yz0123UVWXXklG"),
+            Err(DecodeErr::UnexpectedEnd { position: 14 })
+        );
+    }
+
+    #[test]
+    fn expects_a_tile_after_the_drawn_marker() {
+        for byte in [b'+', b'B', b'!'] {
+            let input = format!("yz0123UVWXXklG{}", byte as char);
+            assert_eq!(
+                Hand::from_string(&input),
+                Err(DecodeErr::InvalidCharacter {
+                    byte,
+                    position: 14,
+                    expected: EXPECTED_TILE,
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_repeated_markers() {
+        assert_eq!(
+            Hand::from_string("yz0123UVWXXkGlGm"),
+            Err(DecodeErr::InvalidCharacter {
+                byte: b'G',
+                position: 14,
+                expected: EXPECTED_UNUSED,
+            })
+        );
+        assert_eq!(
+            Hand::from_string("yz0123UVWXXklHI"),
+            Err(DecodeErr::InvalidCharacter {
+                byte: b'I',
+                position: 14,
+                expected: EXPECTED_UNUSED,
+            })
+        );
+    }
+}
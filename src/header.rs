@@ -0,0 +1,346 @@
+use std::fmt;
+
+use crate::*;
+
+/// The version of the plain text format written by this crate
+pub const FORMAT_VERSION: u8 = 1;
+
+/// Introduces a header. Byte `0x00` is never a tile, so a header can't be mistaken
+/// for the start of a bare tile string
+const INTRODUCER: u8 = 0x00;
+
+/// Human readable description of the character after the introducer
+const EXPECTED_HEADER: &str = "a format version and payload kind";
+
+/// What an encoded string holds, see [Header]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum PayloadKind {
+    /// A plain list of tiles, as written by [Suit::to_string]
+    Tiles,
+    /// A [Hand]
+    Hand,
+    /// 河 _(kawa)_, the discards of a single player in order
+    Pond,
+    /// The wall of a whole game, stored as the seed and rules it was shuffled from
+    Game,
+}
+
+impl PayloadKind {
+    const fn from_bits(bits: u8) -> PayloadKind {
+        match bits {
+            0 => PayloadKind::Tiles,
+            1 => PayloadKind::Hand,
+            2 => PayloadKind::Pond,
+            _ => PayloadKind::Game,
+        }
+    }
+}
+
+/// Two characters at the start of an encoded string saying which version of the
+/// format wrote it and what kind of payload follows. The first is always `A`, the
+/// second holds the version in its upper three bits, whether the string ends in
+/// check characters in the next bit and the [PayloadKind] in the lower two.
+///
+/// ```rust
+/// # use mahjong_encoding::*;
+/// let header = Header::new(PayloadKind::Hand);
+///
+/// assert_eq!(header.to_string(), "AJ");
+/// assert_eq!(Header::parse("AJyz0"), Ok(Some((header, 2))));
+/// assert_eq!(Header::parse("yz0"), Ok(None));
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Header {
+    /// The format version, see [FORMAT_VERSION]
+    pub version: u8,
+    /// What follows the header
+    pub kind: PayloadKind,
+    /// Whether the string ends in two check characters, see [Payload::to_checked_string]
+    pub checksum: bool,
+}
+
+impl Header {
+    /// A header for the current [FORMAT_VERSION], without check characters
+    pub const fn new(kind: PayloadKind) -> Header {
+        Header {
+            version: FORMAT_VERSION,
+            kind,
+            checksum: false,
+        }
+    }
+
+    /// Reads the header at the start of `input`, along with how many bytes it took
+    /// up. `None` if the input has no header, i.e. is a bare tile string.
+    /// Can throw a [DecodeErr]
+    pub fn parse(input: &str) -> Result<Option<(Header, usize)>, DecodeErr> {
+        let input = input.as_bytes();
+        if input.first() != Some(&ALPHABET[INTRODUCER as usize]) {
+            return Ok(None);
+        }
+
+        let &byte = input
+            .get(1)
+            .ok_or(DecodeErr::UnexpectedEnd { position: 1 })?;
+        let err = DecodeErr::InvalidCharacter {
+            byte,
+            position: 1,
+            expected: EXPECTED_HEADER,
+        };
+        let value = value_of(byte).ok_or(err)?;
+
+        let version = value >> 3;
+        if version == 0 || version > FORMAT_VERSION {
+            return Err(DecodeErr::UnknownVersion { version });
+        }
+
+        let header = Header {
+            version,
+            kind: PayloadKind::from_bits(value & 0b11),
+            checksum: value & 0b100 != 0,
+        };
+        Ok(Some((header, 2)))
+    }
+}
+
+impl fmt::Display for Header {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = self.version << 3 | (self.checksum as u8) << 2 | self.kind as u8;
+
+        write!(
+            f,
+            "{}{}",
+            ALPHABET[INTRODUCER as usize] as char, ALPHABET[value as usize] as char
+        )
+    }
+}
+
+/// Anything that can be written after a [Header]
+///
+/// ```rust
+/// # use mahjong_encoding::*;
+/// let pond = Payload::Pond(Suit::from_mpsz("19m7z").unwrap());
+/// let text = pond.to_string();
+///
+/// assert!(text.starts_with("AK"));
+/// assert_eq!(Payload::from_string(&text), Ok(pond));
+///
+/// // strings without a header are read as plain tiles
+/// assert!(matches!(Payload::from_string("yz0"), Ok(Payload::Tiles(_))));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Payload {
+    /// A plain list of tiles
+    Tiles(Vec<Suit>),
+    /// A single player's hand
+    Hand(Hand),
+    /// A single player's discards, in order
+    Pond(Vec<Suit>),
+    /// A game's [Wall], so the deal can be shared and replayed exactly. Only the
+    /// seed and [WallRules] are written, so reading it back gives the wall as built,
+    /// before any draws. A wall that has been drawn from won't be `==` to the one
+    /// read back
+    Game(Wall),
+}
+
+impl Payload {
+    /// What kind of payload this is
+    pub fn kind(&self) -> PayloadKind {
+        match self {
+            Payload::Tiles(_) => PayloadKind::Tiles,
+            Payload::Hand(_) => PayloadKind::Hand,
+            Payload::Pond(_) => PayloadKind::Pond,
+            Payload::Game(_) => PayloadKind::Game,
+        }
+    }
+
+    /// Writes the payload with a [Header] and two check characters at the end, so a
+    /// mistyped character is caught when it is read back
+    ///
+    /// ```
+    /// # use mahjong_encoding::*;
+    /// let text = Payload::Tiles(Suit::from_mpsz("123m").unwrap()).to_checked_string();
+    /// assert!(Payload::from_string(&text).is_ok());
+    ///
+    /// let typo = text.replacen('y', "x", 1);
+    /// assert_eq!(
+    ///     Payload::from_string(&typo),
+    ///     Err(DecodeErr::ChecksumMismatch { likely_position: Some(3) })
+    /// );
+    /// ```
+    pub fn to_checked_string(&self) -> String {
+        self.to_checked_string_with(Alphabet::Standard)
+    }
+
+    /// Writes the payload with a [Header], using the characters of `alphabet`
+    pub fn to_string_with(&self, alphabet: Alphabet) -> String {
+        alphabet.translate(self.to_string())
+    }
+
+    /// Writes the payload with a [Header] and check characters, using the characters
+    /// of `alphabet`, see [Payload::to_checked_string]
+    pub fn to_checked_string_with(&self, alphabet: Alphabet) -> String {
+        let header = Header {
+            checksum: true,
+            ..Header::new(self.kind())
+        };
+
+        let mut text = format!("{header}{}", self.body());
+        checksum::append(&mut text);
+        alphabet.translate(text)
+    }
+
+    /// Reads a string with or without a [Header], dispatching on the kind of payload
+    /// it says follows and checking the check characters if it has them. Strings
+    /// without a header are read as [Payload::Tiles]. Can throw a [DecodeErr]
+    pub fn from_string(input: &str) -> Result<Payload, DecodeErr> {
+        let Some((header, length)) = Header::parse(input)? else {
+            return Suit::from_string(input).map(Payload::Tiles);
+        };
+
+        let input = match header.checksum {
+            true if input.len() < length + 2 => {
+                return Err(DecodeErr::UnexpectedEnd {
+                    position: input.len(),
+                })
+            }
+            true => checksum::verify(input)?,
+            false => input,
+        };
+        let body = &input[length..];
+
+        match header.kind {
+            PayloadKind::Tiles => Suit::from_string(body).map(Payload::Tiles),
+            PayloadKind::Hand => Hand::from_string(body).map(Payload::Hand),
+            PayloadKind::Pond => Suit::from_string(body).map(Payload::Pond),
+            PayloadKind::Game => Wall::decode(body).map(Payload::Game),
+        }
+        .map_err(|err| err.offset(length))
+    }
+}
+
+/// Writes the payload with a [Header] for the current [FORMAT_VERSION]
+impl fmt::Display for Payload {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", Header::new(self.kind()), self.body())
+    }
+}
+
+impl Payload {
+    fn body(&self) -> String {
+        match self {
+            Payload::Tiles(tiles) | Payload::Pond(tiles) => Suit::to_string(tiles),
+            Payload::Hand(hand) => hand.to_string(),
+            Payload::Game(wall) => wall.encode(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn round_trips_every_payload() {
+        let tiles = Suit::from_mpsz("123m406p").unwrap();
+        let hand = Hand::from_string("yz0123UVWXXkl").unwrap();
+
+        let wall = Wall::new(42, WallRules::default()).unwrap();
+
+        for payload in [
+            Payload::Tiles(tiles.clone()),
+            Payload::Hand(hand),
+            Payload::Pond(tiles),
+            Payload::Game(wall),
+        ] {
+            assert_eq!(Payload::from_string(&payload.to_string()), Ok(payload));
+        }
+    }
+
+    #[test]
+    fn reads_walls_back_before_any_draws() {
+        let mut wall = Wall::new(42, WallRules::default()).unwrap();
+        wall.deal();
+        let text = Payload::Game(wall.clone()).to_string();
+
+        assert_ne!(Payload::from_string(&text), Ok(Payload::Game(wall)));
+        assert_eq!(
+            Payload::from_string(&text),
+            Ok(Payload::Game(Wall::new(42, WallRules::default()).unwrap()))
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_versions_and_kinds() {
+        assert_eq!(
+            Payload::from_string("AQyz0"),
+            Err(DecodeErr::UnknownVersion { version: 2 })
+        );
+        assert_eq!(
+            Payload::from_string("AByz0"),
+            Err(DecodeErr::UnknownVersion { version: 0 })
+        );
+        assert_eq!(
+            Payload::from_string("A!yz0"),
+            Err(DecodeErr::InvalidCharacter {
+                byte: b'!',
+                position: 1,
+                expected: EXPECTED_HEADER,
+            })
+        );
+        assert_eq!(
+            Payload::from_string("AL"),
+            Err(DecodeErr::UnexpectedEnd { position: 2 })
+        );
+    }
+
+    #[test]
+    fn checks_checked_payloads() {
+        let hand = Payload::Hand(Hand::from_string("yz0123UVWXXkl").unwrap());
+        let text = hand.to_checked_string();
+
+        assert!(text.starts_with("AN"));
+        assert_eq!(Payload::from_string(&text), Ok(hand));
+        assert!(Payload::from_string(&text[..text.len() - 1]).is_err());
+        assert_eq!(
+            Payload::from_string("AM"),
+            Err(DecodeErr::UnexpectedEnd { position: 2 })
+        );
+    }
+
+    #[test]
+    fn reads_either_alphabet() {
+        let hand = Hand::new(
+            Suit::from_mpsz("123m406p789s1z").unwrap(),
+            vec![Meld::new(MeldKind::Pon, Suit::from_mpsz("777z").unwrap()).unwrap()],
+            None,
+            Riichi::None,
+            vec![Flower::Bamboo, Flower::Winter],
+        )
+        .unwrap();
+        let payload = Payload::Hand(hand);
+
+        let standard = payload.to_checked_string();
+        let url_safe = payload.to_checked_string_with(Alphabet::UrlSafe);
+
+        assert!(standard.contains("+/"));
+        assert_eq!(url_safe, standard.replace('+', "-").replace('/', "_"));
+        assert_eq!(Payload::from_string(&standard), Ok(payload.clone()));
+        assert_eq!(Payload::from_string(&url_safe), Ok(payload));
+    }
+
+    #[test]
+    fn reports_positions_in_the_whole_input() {
+        assert_eq!(
+            Payload::from_string("AIyz!"),
+            Err(DecodeErr::InvalidCharacter {
+                byte: b'!',
+                position: 4,
+                expected: EXPECTED_ALPHABET,
+            })
+        );
+        assert_eq!(
+            Payload::from_string("A"),
+            Err(DecodeErr::UnexpectedEnd { position: 1 })
+        );
+    }
+}
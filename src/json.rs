@@ -0,0 +1,405 @@
+//! Just enough JSON to read and write game logs. Numbers are kept as `f64`, which
+//! holds every integer a log can contain exactly.
+
+use std::fmt;
+
+use crate::*;
+
+const EXPECTED_VALUE: &str = "a JSON value";
+const EXPECTED_SEPARATOR: &str = "a comma or closing bracket";
+const EXPECTED_KEY: &str = "a quoted object key";
+const EXPECTED_COLON: &str = "a colon";
+const EXPECTED_ESCAPE: &str = "a valid escape sequence";
+const EXPECTED_END: &str = "the end of the input";
+const EXPECTED_DEPTH: &str = "at most 64 nested arrays and objects";
+
+/// How deeply arrays and objects can nest, logs never come close
+const MAX_DEPTH: usize = 64;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    /// Keys in document order, so a log is written back the way it was read
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    /// Parses a whole document, anything but whitespace after the value is an error
+    pub(crate) fn parse(input: &str) -> Result<Json, DecodeErr> {
+        let mut parser = Parser {
+            input,
+            bytes: input.as_bytes(),
+            position: 0,
+            depth: 0,
+        };
+
+        let value = parser.value()?;
+        parser.skip_whitespace();
+        match parser.bytes.get(parser.position) {
+            Some(_) => Err(parser.invalid(EXPECTED_END)),
+            None => Ok(value),
+        }
+    }
+
+    /// The value of a key, if this is an object that has it
+    pub(crate) fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(fields) => fields
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_array(&self) -> Option<&[Json]> {
+        match self {
+            Json::Array(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    /// The value as an integer, `None` unless it is a whole number
+    pub(crate) fn as_i64(&self) -> Option<i64> {
+        match self {
+            Json::Number(value) if value.fract() == 0.0 => Some(*value as i64),
+            _ => None,
+        }
+    }
+}
+
+impl From<i64> for Json {
+    fn from(value: i64) -> Json {
+        Json::Number(value as f64)
+    }
+}
+
+impl From<&str> for Json {
+    fn from(value: &str) -> Json {
+        Json::String(value.to_string())
+    }
+}
+
+/// Writes compact JSON, without any whitespace
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Json::Null => write!(f, "null"),
+            Json::Bool(value) => write!(f, "{value}"),
+            Json::Number(value) if value.fract() == 0.0 && value.abs() < 1e15 => {
+                write!(f, "{}", *value as i64)
+            }
+            Json::Number(value) => write!(f, "{value}"),
+            Json::String(value) => write_string(f, value),
+            Json::Array(values) => {
+                write!(f, "[")?;
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{value}")?;
+                }
+                write!(f, "]")
+            }
+            Json::Object(fields) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write_string(f, key)?;
+                    write!(f, ":{value}")?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+fn write_string(f: &mut fmt::Formatter<'_>, value: &str) -> fmt::Result {
+    write!(f, "\"")?;
+    for c in value.chars() {
+        match c {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{c}")?,
+        }
+    }
+    write!(f, "\"")
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    bytes: &'a [u8],
+    position: usize,
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn invalid(&self, expected: &'static str) -> DecodeErr {
+        match self.bytes.get(self.position) {
+            Some(&byte) => DecodeErr::InvalidCharacter {
+                byte,
+                position: self.position,
+                expected,
+            },
+            None => DecodeErr::UnexpectedEnd {
+                position: self.position,
+            },
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self
+            .bytes
+            .get(self.position)
+            .is_some_and(u8::is_ascii_whitespace)
+        {
+            self.position += 1;
+        }
+    }
+
+    /// Consumes `word` if the input continues with it
+    fn eat(&mut self, word: &str) -> bool {
+        let found = self.input[self.position..].starts_with(word);
+        if found {
+            self.position += word.len();
+        }
+        found
+    }
+
+    fn value(&mut self) -> Result<Json, DecodeErr> {
+        self.skip_whitespace();
+
+        match self.bytes.get(self.position) {
+            Some(b'{') => self.object(),
+            Some(b'[') => self.array(),
+            Some(b'"') => Ok(Json::String(self.string()?)),
+            Some(b'-' | b'0'..=b'9') => self.number(),
+            _ if self.eat("null") => Ok(Json::Null),
+            _ if self.eat("true") => Ok(Json::Bool(true)),
+            _ if self.eat("false") => Ok(Json::Bool(false)),
+            _ => Err(self.invalid(EXPECTED_VALUE)),
+        }
+    }
+
+    /// Reads the items between `open` and `close`, calling `item` for each one
+    fn list(
+        &mut self,
+        close: u8,
+        mut item: impl FnMut(&mut Self) -> Result<(), DecodeErr>,
+    ) -> Result<(), DecodeErr> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.invalid(EXPECTED_DEPTH));
+        }
+        self.depth += 1;
+
+        self.position += 1;
+        self.skip_whitespace();
+        if self.bytes.get(self.position) == Some(&close) {
+            self.position += 1;
+            self.depth -= 1;
+            return Ok(());
+        }
+
+        loop {
+            item(self)?;
+            self.skip_whitespace();
+
+            match self.bytes.get(self.position) {
+                Some(b',') => self.position += 1,
+                Some(byte) if *byte == close => {
+                    self.position += 1;
+                    self.depth -= 1;
+                    return Ok(());
+                }
+                _ => return Err(self.invalid(EXPECTED_SEPARATOR)),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<Json, DecodeErr> {
+        let mut values = vec![];
+        self.list(b']', |parser| {
+            values.push(parser.value()?);
+            Ok(())
+        })?;
+
+        Ok(Json::Array(values))
+    }
+
+    fn object(&mut self) -> Result<Json, DecodeErr> {
+        let mut fields = vec![];
+        self.list(b'}', |parser| {
+            parser.skip_whitespace();
+            if parser.bytes.get(parser.position) != Some(&b'"') {
+                return Err(parser.invalid(EXPECTED_KEY));
+            }
+            let key = parser.string()?;
+
+            parser.skip_whitespace();
+            if !parser.eat(":") {
+                return Err(parser.invalid(EXPECTED_COLON));
+            }
+            fields.push((key, parser.value()?));
+            Ok(())
+        })?;
+
+        Ok(Json::Object(fields))
+    }
+
+    fn number(&mut self) -> Result<Json, DecodeErr> {
+        let start = self.position;
+        let length = self.input[start..]
+            .find(|c: char| !matches!(c, '0'..='9' | '-' | '+' | '.' | 'e' | 'E'))
+            .unwrap_or(self.input.len() - start);
+
+        let value = self.input[start..start + length]
+            .parse()
+            .map_err(|_| self.invalid(EXPECTED_VALUE))?;
+        self.position += length;
+        Ok(Json::Number(value))
+    }
+
+    fn string(&mut self) -> Result<String, DecodeErr> {
+        self.position += 1;
+        let mut output = String::new();
+
+        loop {
+            let rest = &self.input[self.position..];
+            let Some(end) = rest.find(['"', '\\']) else {
+                return Err(DecodeErr::UnexpectedEnd {
+                    position: self.input.len(),
+                });
+            };
+            output.push_str(&rest[..end]);
+            self.position += end;
+
+            if self.eat("\"") {
+                return Ok(output);
+            }
+
+            self.position += 1;
+            let escaped = match self.bytes.get(self.position) {
+                Some(b'"') => '"',
+                Some(b'\\') => '\\',
+                Some(b'/') => '/',
+                Some(b'b') => '\u{8}',
+                Some(b'f') => '\u{c}',
+                Some(b'n') => '\n',
+                Some(b'r') => '\r',
+                Some(b't') => '\t',
+                Some(b'u') => {
+                    self.position += 1;
+                    let high = self.hex()?;
+                    let code = match (0xD800..0xDC00).contains(&high) {
+                        true => match self.low_surrogate() {
+                            Some(low) => 0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00),
+                            None => high,
+                        },
+                        false => high,
+                    };
+
+                    output.push(char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER));
+                    continue;
+                }
+                _ => return Err(self.invalid(EXPECTED_ESCAPE)),
+            };
+            output.push(escaped);
+            self.position += 1;
+        }
+    }
+
+    /// Four hex digits of a `\u` escape
+    fn hex(&mut self) -> Result<u32, DecodeErr> {
+        let value = self
+            .input
+            .get(self.position..self.position + 4)
+            .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+            .ok_or(self.invalid(EXPECTED_ESCAPE))?;
+
+        self.position += 4;
+        Ok(value)
+    }
+
+    /// The second half of a surrogate pair, if the input continues with one. Anything
+    /// else is left to be read on its own
+    fn low_surrogate(&mut self) -> Option<u32> {
+        let low = self
+            .input
+            .get(self.position..self.position + 6)?
+            .strip_prefix("\\u")
+            .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+            .filter(|low| (0xDC00..0xE000).contains(low))?;
+
+        self.position += 6;
+        Some(low)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn round_trips_documents() {
+        let input = r#" {"a": [1, -2.5, true, null], "b": "\"é🀄\n", "c": {}} "#;
+        let value = Json::parse(input).unwrap();
+
+        assert_eq!(
+            value.get("a").unwrap().as_array().unwrap()[0].as_i64(),
+            Some(1)
+        );
+        assert_eq!(value.get("b").unwrap().as_str(), Some("\"é🀄\n"));
+        assert_eq!(
+            value.to_string(),
+            r#"{"a":[1,-2.5,true,null],"b":"\"é🀄\n","c":{}}"#
+        );
+        assert_eq!(Json::parse(&value.to_string()), Ok(value));
+    }
+
+    #[test]
+    fn reports_malformed_input() {
+        assert_eq!(
+            Json::parse("[1 2]"),
+            Err(DecodeErr::InvalidCharacter {
+                byte: b'2',
+                position: 3,
+                expected: EXPECTED_SEPARATOR,
+            })
+        );
+        assert_eq!(
+            Json::parse(r#"{"a":"#),
+            Err(DecodeErr::UnexpectedEnd { position: 5 })
+        );
+        assert!(Json::parse("[1] x").is_err());
+        assert!(Json::parse(r#""\q""#).is_err());
+        assert_eq!(
+            Json::parse(r#""\uDBFF\u0000\uD83C\uDC04""#),
+            Ok(Json::String("\u{FFFD}\0🀄".to_string()))
+        );
+        assert_eq!(
+            Json::parse(&"[".repeat(100_000)),
+            Err(DecodeErr::InvalidCharacter {
+                byte: b'[',
+                position: MAX_DEPTH,
+                expected: EXPECTED_DEPTH,
+            })
+        );
+    }
+}
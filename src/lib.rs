@@ -4,18 +4,77 @@
 //! exported in a plain text format or reimported from text. This is extra useful
 //! when transmitting over plain text formats such as email or sms.
 //!
+//! Hands can also be read and written in the MPSZ notation used by Tenhou and most
+//! forums, see [Suit::to_mpsz] and [Suit::from_mpsz], or as Unicode tiles, see
+//! [Suit::to_unicode] and [Suit::from_unicode]. Whole games can be imported from
+//! Tenhou's `.mjlog` replays, see [Replay::from_mjlog], and read or written in
+//! Tenhou's JSON log format, see [TenhouLog]. Bots can talk to mjai servers with
+//! [MjaiMessage].
+//!
+//! Simulations can shuffle and deal a [Wall] from a seed, which can itself be shared
+//! in the text format as a [Payload::Game].
+//!
+//! With the `serde` feature tiles can be serialized too, see [serde_formats] for the
+//! representations on offer.
+//!
 
 #![warn(missing_docs)]
 #![doc(html_logo_url = "https://boxler.me/img/red_reagon.jpg")]
+mod checksum;
+mod compact;
+mod counts;
+mod decompose;
+mod dora;
+mod error;
+mod hand;
+mod header;
+mod json;
 mod lookup;
+mod meld;
+mod mjai;
+mod mjlog;
+mod mpsz;
+mod score;
+#[cfg(feature = "serde")]
+pub mod serde_formats;
+mod shanten;
+mod tenhou6;
+mod tile;
+mod ukeire;
+mod unicode;
+mod wall;
+mod xml;
+mod yaku;
 
-use lookup::{ALPHABET, INDEX};
+pub use compact::COMPACT_MAX_TILES;
+pub use counts::{TileCounts, TILE_KINDS};
+pub use decompose::{Decomposition, Decompositions, Group, Shape, Wait};
+pub use error::DecodeErr;
+pub use hand::{Flower, Hand};
+pub use header::{Header, Payload, PayloadKind, FORMAT_VERSION};
+pub use lookup::Alphabet;
+use lookup::{value_of, ALPHABET, INDEX};
+pub use meld::{Claim, Meld, MeldKind, Seat};
+pub use mjai::MjaiMessage;
+pub use mjlog::{Agari, Event, Replay, Round};
+pub use score::{Fu, FuReason, Limit, Payment, Score};
+pub use shanten::Shanten;
+pub use tenhou6::{Dahai, RoundResult, Take, TenhouLog, TenhouRound, TenhouWin};
+pub use tile::{Rank, Tile, TileErr};
+pub use ukeire::{Acceptance, Discard, Ukeire};
+pub use unicode::RED_MARKER;
+pub use wall::{Deal, Wall, WallRules, WallTile};
+pub use yaku::{Evaluation, Riichi, Win, WinType, Yaku};
+
+/// Human readable description of [lookup::ALPHABET], used when reporting errors
+const EXPECTED_ALPHABET: &str = "one of A-Z, a-z, 0-9, +, /, - or _";
 
 /// 数牌 _(suupai)_,
 /// used to define a tile
 ///
 /// The Suit used to define a tile. A hand, for example should be a `Vec<Suit>`.
-/// Used in conjunction with [RED_FIVE], [Dragon] or [Wind].
+/// Used in conjunction with [RED_FIVE], [Dragon] or [Wind]. Ranks outside of `1`–`9`
+/// and [RED_FIVE] don't refer to a tile, see [Tile] for a checked alternative.
 ///
 /// ```rust
 /// # use mahjong_encoding::*;
@@ -25,7 +84,9 @@ use lookup::{ALPHABET, INDEX};
 ///     Suit::Dots(7u8),
 /// ];
 /// ```
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "serde_formats::Structured"))]
 pub enum Suit {
     /// 餅子 _(pinzu)_
     Dots(u8),
@@ -47,40 +108,55 @@ pub enum Suit {
 /// # use mahjong_encoding::*;
 /// Suit::Dragon(Dragon::Green);
 /// ```
-#[derive(Debug, Copy, Clone, PartialEq)]
+///
+/// Dragons are ordered white, green, red.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Dragon {
     /// 白 _(shiro)_
     White,
-    /// 中 _(chun)_
-    Red,
     /// 發 _(hatsu)_
     Green,
+    /// 中 _(chun)_
+    Red,
 }
 
 /// 風牌 _(fompai)_,
 /// Wind honours, to be used as part of a suit
-#[derive(Debug, Copy, Clone, PartialEq)]
+///
+/// Winds are ordered east, south, west, north.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Wind {
-    /// 南 _(nan)_
-    South,
     /// 東 _(ton)_
     East,
-    /// 北 _(pei)_
-    North,
+    /// 南 _(nan)_
+    South,
     /// 西 _(sha)_
     West,
+    /// 北 _(pei)_
+    North,
+}
+
+/// Tiles are ordered the way a hand is usually displayed: 萬子 _(manzu)_, 餅子 _(pinzu)_,
+/// 索子 _(so-zu)_, then [Wind] and [Dragon]. A red five sorts right after the plain five
+/// of its suit.
+impl Ord for Suit {
+    fn cmp(&self, other: &Suit) -> std::cmp::Ordering {
+        self.sort_key().cmp(&other.sort_key())
+    }
+}
+
+impl PartialOrd for Suit {
+    fn partial_cmp(&self, other: &Suit) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
 }
 
 /// Red Five
 /// 赤牌 _(akapai)_
 pub const RED_FIVE: u8 = 0xA;
 
-/// Errors that can be thrown when converting from [&str] -> [`Vec<Suit>`]
-pub enum DecodeErr {
-    /// The character you used does not refer to a tile
-    InvalidCharacter,
-}
-
 /// Defines what can be converted from `T` into a [u8]
 pub trait ToByte {
     /// Converts from `T` into [u8]
@@ -92,6 +168,8 @@ pub trait ToByte {
     fn to_byte(&self) -> u8;
 }
 
+/// Only the low nibble of a numbered tile's rank is kept, so a [Suit] that isn't
+/// [valid](Suit::is_valid) may encode as a different tile. Use [Tile] to rule that out.
 impl ToByte for Suit {
     fn to_byte(&self) -> u8 {
         // USE NO VALUE ABOVE 0x3F!!!
@@ -148,34 +226,179 @@ impl Suit {
         input
             .as_bytes()
             .iter()
-            .map(|tile| match INDEX[*tile as usize] {
+            .enumerate()
+            .map(|(position, tile)| match INDEX[*tile as usize] {
                 Some(v) => Ok(v),
-                None => Err(DecodeErr::InvalidCharacter),
+                None => Err(DecodeErr::InvalidCharacter {
+                    byte: *tile,
+                    position,
+                    expected: EXPECTED_ALPHABET,
+                }),
             })
             .collect()
     }
+
+    /// Checks that a list of concealed tiles could make up a hand: no more than four
+    /// copies of any tile (red fives count as fives) and a tile count that leaves
+    /// room for called melds, i.e. 14, 13, 11, 10 and so on down to 1.
+    ///
+    /// ```
+    /// # use mahjong_encoding::*;
+    /// let hand = Suit::from_string("yz0123UVWXXklm").unwrap();
+    /// assert!(Suit::validate_hand(&hand).is_ok());
+    /// assert!(Suit::validate_hand(&hand[..12]).is_err());
+    /// ```
+    pub fn validate_hand(hand: &[Suit]) -> Result<(), DecodeErr> {
+        if hand.is_empty() || hand.len() > 14 || hand.len().is_multiple_of(3) {
+            return Err(DecodeErr::InvalidHandLength { length: hand.len() });
+        }
+
+        for tile in hand {
+            let count = hand.iter().filter(|other| other.is_same_tile(tile)).count();
+
+            if count > 4 {
+                return Err(DecodeErr::TooManyCopies {
+                    tile: tile.normalize(),
+                    count,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Whether this is a 赤牌 _(akapai)_, see [RED_FIVE]
+    ///
+    /// ```
+    /// # use mahjong_encoding::*;
+    /// assert!(Suit::Bamboo(RED_FIVE).is_red());
+    /// assert!(!Suit::Bamboo(5).is_red());
+    /// ```
+    pub const fn is_red(&self) -> bool {
+        matches!(
+            self,
+            Suit::Dots(RED_FIVE) | Suit::Bamboo(RED_FIVE) | Suit::Characters(RED_FIVE)
+        )
+    }
+
+    /// Turns a red five into a plain five, any other tile is returned as is
+    ///
+    /// ```
+    /// # use mahjong_encoding::*;
+    /// assert_eq!(Suit::Dots(RED_FIVE).normalize(), Suit::Dots(5));
+    /// assert_eq!(Suit::Dots(3).normalize(), Suit::Dots(3));
+    /// ```
+    pub const fn normalize(self) -> Suit {
+        match self {
+            Suit::Dots(RED_FIVE) => Suit::Dots(5),
+            Suit::Bamboo(RED_FIVE) => Suit::Bamboo(5),
+            Suit::Characters(RED_FIVE) => Suit::Characters(5),
+            tile => tile,
+        }
+    }
+
+    /// Whether two tiles are the same for the shape of a hand, i.e. a red five is
+    /// treated like any other five of its suit
+    ///
+    /// ```
+    /// # use mahjong_encoding::*;
+    /// assert!(Suit::Characters(RED_FIVE).is_same_tile(&Suit::Characters(5)));
+    /// assert!(!Suit::Characters(RED_FIVE).is_same_tile(&Suit::Dots(5)));
+    /// ```
+    pub fn is_same_tile(&self, other: &Suit) -> bool {
+        self.normalize() == other.normalize()
+    }
+
+    /// Sorts a hand into the canonical display order, see [Ord for Suit](#impl-Ord-for-Suit)
+    ///
+    /// ```
+    /// # use mahjong_encoding::*;
+    /// let mut hand = Suit::from_mpsz("7z1s0p5p9m1z").unwrap();
+    /// Suit::sort_hand(&mut hand);
+    /// assert_eq!(Suit::to_mpsz(&hand), "9m50p1s17z");
+    /// ```
+    pub fn sort_hand(hand: &mut [Suit]) {
+        hand.sort_unstable();
+    }
+
+    /// Red fives sort just after the plain five. Wide enough that every rank gets its
+    /// own key, so tiles that aren't valid still order consistently with `Eq`
+    fn sort_key(&self) -> (u8, u16) {
+        let rank = |n: u8| match n {
+            RED_FIVE => 11,
+            n => n as u16 * 2,
+        };
+
+        match self {
+            Suit::Characters(n) => (0, rank(*n)),
+            Suit::Dots(n) => (1, rank(*n)),
+            Suit::Bamboo(n) => (2, rank(*n)),
+            Suit::Wind(wind) => (3, *wind as u16),
+            Suit::Dragon(dragon) => (4, *dragon as u16),
+        }
+    }
+
+    /// The number on a numbered tile, red fives are `5`. Honours have no number
+    ///
+    /// ```
+    /// # use mahjong_encoding::*;
+    /// assert_eq!(Suit::Dots(RED_FIVE).number(), Some(5));
+    /// assert_eq!(Suit::Dragon(Dragon::Red).number(), None);
+    /// ```
+    pub const fn number(&self) -> Option<u8> {
+        match self.normalize() {
+            Suit::Dots(n) | Suit::Bamboo(n) | Suit::Characters(n) => Some(n),
+            Suit::Wind(_) | Suit::Dragon(_) => None,
+        }
+    }
+
+    /// Whether this is a 字牌 _(jihai)_, i.e. a [Wind] or a [Dragon]
+    pub const fn is_honour(&self) -> bool {
+        matches!(self, Suit::Wind(_) | Suit::Dragon(_))
+    }
+
+    /// Whether this is a 老頭牌 _(rōtōhai)_, a one or a nine
+    pub const fn is_terminal(&self) -> bool {
+        matches!(self.number(), Some(1 | 9))
+    }
+
+    /// Whether this is a 中張牌 _(chunchanpai)_, a two to eight
+    pub const fn is_simple(&self) -> bool {
+        matches!(self.number(), Some(2..=8))
+    }
+
+    /// Counts the 赤ドラ _(aka dora)_ in a hand
+    ///
+    /// ```
+    /// # use mahjong_encoding::*;
+    /// let hand = Suit::from_mpsz("406m55p0s").unwrap();
+    /// assert_eq!(Suit::count_red(&hand), 2);
+    /// ```
+    pub fn count_red(hand: &[Suit]) -> usize {
+        hand.iter().filter(|tile| tile.is_red()).count()
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use std::collections::HashSet;
+    use std::collections::{BTreeSet, HashSet};
     use std::iter::zip;
 
     fn get_all_tiles() -> Vec<Suit> {
         let mut vec = vec![];
 
         for suit in [Suit::Dots, Suit::Bamboo, Suit::Characters] {
-            for i in 0..0xA {
+            for i in (1..=9).chain([RED_FIVE]) {
                 vec.push(suit(i));
             }
         }
 
-        for wind in [Wind::South, Wind::East, Wind::North, Wind::West] {
+        for wind in [Wind::East, Wind::South, Wind::West, Wind::North] {
             vec.push(Suit::Wind(wind));
         }
 
-        for dragon in [Dragon::White, Dragon::Red, Dragon::Green] {
+        for dragon in [Dragon::White, Dragon::Green, Dragon::Red] {
             vec.push(Suit::Dragon(dragon));
         }
 
@@ -226,6 +449,171 @@ mod test {
         zip(tiles, input).for_each(|(a, b)| assert_eq!(a, b));
     }
 
+    #[test]
+    fn reports_position_of_invalid_character() {
+        assert_eq!(
+            Suit::from_string("yz0!"),
+            Err(DecodeErr::InvalidCharacter {
+                byte: b'!',
+                position: 3,
+                expected: EXPECTED_ALPHABET,
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_fifth_copy_of_a_tile() {
+        let hand = Suit::from_string("UUUUU").unwrap();
+
+        assert_eq!(
+            Suit::validate_hand(&hand),
+            Err(DecodeErr::TooManyCopies {
+                tile: Suit::Dots(4),
+                count: 5,
+            })
+        );
+    }
+
+    /// Tiny xorshift generator so the fuzz tests are reproducible without any
+    /// dev-dependencies
+    pub(crate) fn xorshift(state: &mut u64) -> u64 {
+        *state ^= *state << 13;
+        *state ^= *state >> 7;
+        *state ^= *state << 17;
+        *state
+    }
+
+    #[test]
+    fn decodes_every_single_byte_without_panicking() {
+        for byte in 0..=u8::MAX {
+            let input = [byte];
+            let input = String::from_utf8_lossy(&input);
+            let _ = Suit::from_string(&input);
+            let _ = Suit::from_mpsz(&input);
+        }
+    }
+
+    #[test]
+    fn rejects_bytes_outside_the_alphabet() {
+        for byte in 0..=u8::MAX {
+            if !ALPHABET.contains(&byte) {
+                assert_eq!(INDEX[byte as usize], None);
+            }
+        }
+
+        for input in ["{", "|", "~", "\u{7f}", "é", "🀄", "yz0{"] {
+            assert!(matches!(
+                Suit::from_string(input),
+                Err(DecodeErr::InvalidCharacter { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn decodes_every_pair_of_characters_without_panicking() {
+        for a in 0..0x80u8 {
+            for b in 0..0x80u8 {
+                let input = String::from_utf8(vec![a, b]).unwrap();
+                let _ = Suit::from_string(&input);
+                let _ = Suit::from_mpsz(&input);
+            }
+        }
+    }
+
+    #[test]
+    fn decodes_random_strings_without_panicking() {
+        let mut state = 0x9E37_79B9_7F4A_7C15;
+
+        for _ in 0..10_000 {
+            let length = xorshift(&mut state) % 32;
+            let input = (0..length)
+                .filter_map(|_| char::from_u32((xorshift(&mut state) % 0x1_1000) as u32))
+                .collect::<String>();
+
+            if let Err(DecodeErr::InvalidCharacter { position, byte, .. }) =
+                Suit::from_string(&input)
+            {
+                assert_eq!(input.as_bytes()[position], byte);
+            }
+            let _ = Suit::from_mpsz(&input);
+        }
+    }
+
+    #[test]
+    fn round_trips_random_valid_strings() {
+        let mut state = 0x2545_F491_4F6C_DD1D;
+        let valid = ALPHABET
+            .iter()
+            .filter(|byte| INDEX[**byte as usize].is_some())
+            .collect::<Vec<_>>();
+
+        for _ in 0..10_000 {
+            let length = xorshift(&mut state) % 32;
+            let input = (0..length)
+                .map(|_| *valid[xorshift(&mut state) as usize % valid.len()] as char)
+                .collect::<String>();
+            let hand = Suit::from_string(&input).unwrap();
+
+            assert_eq!(Suit::to_string(&hand), input);
+        }
+    }
+
+    #[test]
+    fn round_trips_red_fives() {
+        let tiles = [Suit::Dots(RED_FIVE), Suit::Bamboo(RED_FIVE), Suit::Characters(RED_FIVE)];
+        let encoded = Suit::to_string(&tiles);
+
+        assert_eq!(encoded, "aq6");
+        assert_eq!(Suit::from_string(&encoded).unwrap(), tiles);
+    }
+
+    #[test]
+    fn round_trips_every_tile() {
+        let tiles = get_all_tiles();
+
+        assert_eq!(Suit::from_string(&Suit::to_string(&tiles)).unwrap(), tiles);
+    }
+
+    #[test]
+    fn red_fives_count_towards_copies() {
+        let hand = Suit::from_mpsz("05555m").unwrap();
+
+        assert_eq!(
+            Suit::validate_hand(&hand),
+            Err(DecodeErr::TooManyCopies {
+                tile: Suit::Characters(5),
+                count: 5,
+            })
+        );
+    }
+
+    #[test]
+    fn sorts_hand_canonically() {
+        let mut hand = Suit::from_mpsz("4z7z5z6z3z2z1z9s0s5s1s9p1p9m0m5m1m").unwrap();
+        Suit::sort_hand(&mut hand);
+
+        assert_eq!(Suit::to_mpsz(&hand), "1509m19p1509s1234567z");
+    }
+
+    #[test]
+    fn tiles_can_be_hashed_and_ordered() {
+        let hand = Suit::from_mpsz("11223m11z").unwrap();
+
+        assert_eq!(hand.iter().collect::<HashSet<_>>().len(), 4);
+        assert_eq!(hand.iter().collect::<BTreeSet<_>>().len(), 4);
+        assert!(Suit::Characters(9) < Suit::Dots(1));
+        assert!(Suit::Bamboo(9) < Suit::Wind(Wind::East));
+        assert!(Suit::Wind(Wind::North) < Suit::Dragon(Dragon::White));
+
+        let mut hand = vec![Suit::Dots(200), Suit::Dots(1), Suit::Dots(128)];
+        Suit::sort_hand(&mut hand);
+        assert_eq!(hand, [Suit::Dots(1), Suit::Dots(128), Suit::Dots(200)]);
+        assert_ne!(
+            Suit::Dots(128).cmp(&Suit::Dots(0)),
+            std::cmp::Ordering::Equal
+        );
+    }
+
     #[test]
     fn serializes_hand_correctly() {
         let tiles = [
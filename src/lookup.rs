@@ -7,7 +7,8 @@ pub const ALPHABET: [u8; 64] = [
     0x77, 0x78, 0x79, 0x7a, 0x30, 0x31, 0x32, 0x33, 0x34, 0x35, 0x36, 0x37, 0x38, 0x39, 0x2b, 0x2f,
 ];
 
-pub const INDEX: [Option<Suit>; 123] = [
+// One entry for every possible byte, so any input can be looked up without a bounds check
+pub const INDEX: [Option<Suit>; 256] = [
     None,
     None,
     None,
@@ -62,7 +63,7 @@ pub const INDEX: [Option<Suit>; 123] = [
     Some(Suit::Characters(7)),
     Some(Suit::Characters(8)),
     Some(Suit::Characters(9)),
-    None,
+    Some(Suit::Characters(RED_FIVE)),
     None,
     Some(Suit::Wind(Wind::West)),
     None,
@@ -89,7 +90,7 @@ pub const INDEX: [Option<Suit>; 123] = [
     Some(Suit::Dragon(Dragon::White)),
     None,
     None,
-    Some(Suit::Dots(0)),
+    None,
     Some(Suit::Dots(1)),
     Some(Suit::Dots(2)),
     Some(Suit::Dots(3)),
@@ -105,13 +106,13 @@ pub const INDEX: [Option<Suit>; 123] = [
     None,
     None,
     None,
-    None,
+    Some(Suit::Dots(RED_FIVE)),
     None,
     Some(Suit::Wind(Wind::East)),
     Some(Suit::Dragon(Dragon::Red)),
     None,
     None,
-    Some(Suit::Bamboo(0)),
+    None,
     Some(Suit::Bamboo(1)),
     Some(Suit::Bamboo(2)),
     Some(Suit::Bamboo(3)),
@@ -121,14 +122,216 @@ pub const INDEX: [Option<Suit>; 123] = [
     Some(Suit::Bamboo(7)),
     Some(Suit::Bamboo(8)),
     Some(Suit::Bamboo(9)),
-    None,
+    Some(Suit::Bamboo(RED_FIVE)),
     None,
     Some(Suit::Wind(Wind::North)),
     Some(Suit::Dragon(Dragon::Green)),
     None,
     None,
-    Some(Suit::Characters(0)),
+    None,
     Some(Suit::Characters(1)),
     Some(Suit::Characters(2)),
     Some(Suit::Characters(3)),
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
 ];
+
+/// Which characters stand for the last two base64 values when encoding.
+/// Decoding always accepts either. Tiles never use those values, but flowers, meld
+/// markers and check characters can.
+///
+/// ```rust
+/// # use mahjong_encoding::*;
+/// let hand = Payload::Hand(Hand::from_string("yz0123UVWXXkl").unwrap());
+/// let text = hand.to_string_with(Alphabet::UrlSafe);
+///
+/// assert!(!text.contains(['+', '/']));
+/// assert_eq!(Payload::from_string(&text), Ok(hand));
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum Alphabet {
+    /// Standard base64, ending in `+` and `/`
+    #[default]
+    Standard,
+    /// Base64url, ending in `-` and `_`, which can be put in links without escaping
+    UrlSafe,
+}
+
+impl Alphabet {
+    /// The character that stands for the low six bits of `value`, any higher bits
+    /// are ignored
+    ///
+    /// ```
+    /// # use mahjong_encoding::*;
+    /// assert_eq!(Alphabet::Standard.encode(62), b'+');
+    /// assert_eq!(Alphabet::UrlSafe.encode(62), b'-');
+    /// assert_eq!(Alphabet::Standard.encode(64), b'A');
+    /// ```
+    pub const fn encode(self, value: u8) -> u8 {
+        let value = value & 0x3F;
+        match (self, value) {
+            (Alphabet::UrlSafe, 62) => b'-',
+            (Alphabet::UrlSafe, 63) => b'_',
+            _ => ALPHABET[value as usize],
+        }
+    }
+
+    /// Rewrites text encoded with [Alphabet::Standard] to use this alphabet
+    pub(crate) fn translate(self, text: String) -> String {
+        match self {
+            Alphabet::Standard => text,
+            Alphabet::UrlSafe => text.replace('+', "-").replace('/', "_"),
+        }
+    }
+}
+
+/// Where `byte` sits in [ALPHABET], i.e. the six bits it stands for. The URL safe
+/// `-` and `_` are accepted alongside `+` and `/`
+pub const fn value_of(byte: u8) -> Option<u8> {
+    match byte {
+        b'-' => return Some(62),
+        b'_' => return Some(63),
+        _ => {}
+    }
+
+    let mut value = 0;
+    while value < ALPHABET.len() {
+        if ALPHABET[value] == byte {
+            return Some(value as u8);
+        }
+        value += 1;
+    }
+
+    None
+}
@@ -0,0 +1,439 @@
+use crate::*;
+
+/// Human readable description of the meld markers, used when reporting errors
+const EXPECTED_MARKER: &str = "a meld marker, one of B-F";
+/// Human readable description of the seat a meld was claimed from
+const EXPECTED_SEAT: &str = "a seat, one of A-D";
+
+/// The kind of a [Meld]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum MeldKind {
+    /// チー _(chii)_, a called sequence
+    Chi,
+    /// ポン _(pon)_, a called triplet
+    Pon,
+    /// 大明槓 _(daiminkan)_, a called quad
+    OpenKan,
+    /// 暗槓 _(ankan)_, a quad declared from the concealed hand
+    ClosedKan,
+    /// 加槓 _(kakan)_, a [MeldKind::Pon] upgraded with the fourth tile
+    AddedKan,
+}
+
+impl MeldKind {
+    /// How many tiles a meld of this kind is made of
+    pub const fn tile_count(self) -> usize {
+        match self {
+            MeldKind::Chi | MeldKind::Pon => 3,
+            MeldKind::OpenKan | MeldKind::ClosedKan | MeldKind::AddedKan => 4,
+        }
+    }
+
+    /// The byte that introduces a meld in the plain text format. Tiles never use
+    /// `0x01`–`0x09`, so a marker can't be mistaken for a tile.
+    pub(crate) const fn to_byte(self) -> u8 {
+        match self {
+            MeldKind::Chi => 0x01,
+            MeldKind::Pon => 0x02,
+            MeldKind::OpenKan => 0x03,
+            MeldKind::ClosedKan => 0x04,
+            MeldKind::AddedKan => 0x05,
+        }
+    }
+
+    pub(crate) const fn from_byte(byte: u8) -> Option<MeldKind> {
+        Some(match byte {
+            0x01 => MeldKind::Chi,
+            0x02 => MeldKind::Pon,
+            0x03 => MeldKind::OpenKan,
+            0x04 => MeldKind::ClosedKan,
+            0x05 => MeldKind::AddedKan,
+            _ => return None,
+        })
+    }
+}
+
+/// Where a claimed tile came from, relative to the player who called it
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Seat {
+    /// 上家 _(kamicha)_, the player to the left, who discards just before you
+    Left,
+    /// 対面 _(toimen)_, the player opposite
+    Across,
+    /// 下家 _(shimocha)_, the player to the right
+    Right,
+}
+
+/// The discard that was called to make a [Meld]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Claim {
+    /// The tile that was called
+    pub tile: Suit,
+    /// Who discarded it
+    pub from: Seat,
+}
+
+/// 副露 _(fuuro)_,
+/// a meld set aside from the hand after a call or a kan
+///
+/// ```rust
+/// # use mahjong_encoding::*;
+/// let meld = Meld::new(MeldKind::Pon, Suit::from_mpsz("777z").unwrap()).unwrap();
+///
+/// assert!(meld.is_open());
+/// assert!(!meld.is_kan());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Meld {
+    kind: MeldKind,
+    tiles: Vec<Suit>,
+    claim: Option<Claim>,
+}
+
+impl Meld {
+    /// A meld of the given kind made up of `tiles`, without recording which tile was
+    /// called. A chi must be three consecutive tiles of one numbered suit, the other
+    /// kinds three or four of the same tile. Can throw a [DecodeErr]
+    ///
+    /// ```
+    /// # use mahjong_encoding::*;
+    /// assert!(Meld::new(MeldKind::Chi, Suit::from_mpsz("340m").unwrap()).is_ok());
+    /// assert!(Meld::new(MeldKind::Chi, Suit::from_mpsz("89m1p").unwrap()).is_err());
+    /// assert!(Meld::new(MeldKind::ClosedKan, Suit::from_mpsz("111z").unwrap()).is_err());
+    /// ```
+    pub fn new(kind: MeldKind, mut tiles: Vec<Suit>) -> Result<Meld, DecodeErr> {
+        if let Some(tile) = tiles.iter().find(|tile| !tile.is_valid()) {
+            return Err(DecodeErr::InvalidTile { tile: *tile });
+        }
+
+        Suit::sort_hand(&mut tiles);
+        let first = tiles.first().map(|tile| tile.normalize());
+
+        let valid = tiles.len() == kind.tile_count()
+            && match (kind, first) {
+                (MeldKind::Chi, Some(first)) => {
+                    first.number().is_some()
+                        && first.index().is_some_and(|start| {
+                            start % 9 <= 6
+                                && tiles
+                                    .iter()
+                                    .zip(start..)
+                                    .all(|(tile, index)| tile.index() == Some(index))
+                        })
+                }
+                (_, Some(first)) => tiles.iter().all(|tile| tile.is_same_tile(&first)),
+                (_, None) => false,
+            };
+
+        if !valid {
+            return Err(DecodeErr::InvalidMeld { kind });
+        }
+
+        Ok(Meld {
+            kind,
+            tiles,
+            claim: None,
+        })
+    }
+
+    /// A meld made by calling `claimed` from the player in seat `from`. Only the
+    /// player to the left can be called for a chi, and a closed kan can't be called
+    /// at all. Can throw a [DecodeErr]
+    ///
+    /// ```
+    /// # use mahjong_encoding::*;
+    /// let tiles = Suit::from_mpsz("345p").unwrap();
+    /// let meld = Meld::called(MeldKind::Chi, tiles.clone(), Suit::Dots(4), Seat::Left).unwrap();
+    ///
+    /// assert_eq!(meld.claim().unwrap().tile, Suit::Dots(4));
+    /// assert!(Meld::called(MeldKind::Chi, tiles, Suit::Dots(4), Seat::Right).is_err());
+    /// ```
+    pub fn called(
+        kind: MeldKind,
+        tiles: Vec<Suit>,
+        claimed: Suit,
+        from: Seat,
+    ) -> Result<Meld, DecodeErr> {
+        let mut meld = Meld::new(kind, tiles)?;
+
+        let valid = match kind {
+            MeldKind::Chi => from == Seat::Left,
+            MeldKind::ClosedKan => false,
+            _ => true,
+        };
+        if !valid || !meld.tiles.contains(&claimed) {
+            return Err(DecodeErr::InvalidMeld { kind });
+        }
+
+        meld.claim = Some(Claim {
+            tile: claimed,
+            from,
+        });
+        Ok(meld)
+    }
+
+    /// Upgrades a [MeldKind::Pon] to a [MeldKind::AddedKan] with the fourth copy of
+    /// its tile, keeping the original claim. Can throw a [DecodeErr]
+    ///
+    /// ```
+    /// # use mahjong_encoding::*;
+    /// let pon = Meld::new(MeldKind::Pon, Suit::from_mpsz("555s").unwrap()).unwrap();
+    /// let kan = pon.upgrade(Suit::Bamboo(RED_FIVE)).unwrap();
+    ///
+    /// assert_eq!(kan.kind(), MeldKind::AddedKan);
+    /// assert_eq!(Suit::count_red(kan.tiles()), 1);
+    /// ```
+    pub fn upgrade(&self, tile: Suit) -> Result<Meld, DecodeErr> {
+        if self.kind != MeldKind::Pon {
+            return Err(DecodeErr::InvalidMeld { kind: self.kind });
+        }
+
+        let mut tiles = self.tiles.clone();
+        tiles.push(tile);
+
+        Ok(Meld {
+            claim: self.claim,
+            ..Meld::new(MeldKind::AddedKan, tiles)?
+        })
+    }
+
+    /// The kind of meld
+    pub fn kind(&self) -> MeldKind {
+        self.kind
+    }
+
+    /// The tiles that make up the meld, in canonical order
+    pub fn tiles(&self) -> &[Suit] {
+        &self.tiles
+    }
+
+    /// The tile that was called and who from, if it was recorded
+    pub fn claim(&self) -> Option<Claim> {
+        self.claim
+    }
+
+    /// Whether the meld was called from another player, which opens the hand.
+    /// Only a [MeldKind::ClosedKan] keeps the hand closed.
+    pub fn is_open(&self) -> bool {
+        self.kind != MeldKind::ClosedKan
+    }
+
+    /// Whether the meld is a quad
+    pub fn is_kan(&self) -> bool {
+        matches!(
+            self.kind,
+            MeldKind::OpenKan | MeldKind::ClosedKan | MeldKind::AddedKan
+        )
+    }
+
+    /// The meld as a [Group], quads count as triplets
+    pub fn group(&self) -> Group {
+        let lowest = self.tiles[0].normalize();
+
+        match self.kind {
+            MeldKind::Chi => Group::Sequence(lowest),
+            _ => Group::Triplet(lowest),
+        }
+    }
+
+    /// Converts a list of melds into a plain text string. Each meld is written as a
+    /// marker for its kind, the seat it was claimed from (`A` if it wasn't recorded)
+    /// and its tiles, with the claimed tile first.
+    ///
+    /// ```
+    /// # use mahjong_encoding::*;
+    /// let melds = [
+    ///     Meld::called(MeldKind::Pon, Suit::from_mpsz("777z").unwrap(), Suit::Dragon(Dragon::Red), Seat::Across).unwrap(),
+    ///     Meld::new(MeldKind::ClosedKan, Suit::from_mpsz("1111m").unwrap()).unwrap(),
+    /// ];
+    ///
+    /// assert_eq!(Meld::from_string(&Meld::to_string(&melds)), Ok(melds.to_vec()));
+    /// ```
+    pub fn to_string(melds: &[Meld]) -> String {
+        let mut out = vec![];
+        melds.iter().for_each(|meld| meld.encode(&mut out));
+
+        String::from_utf8(out).unwrap()
+    }
+
+    /// Converts from a plain text string into a list of melds. Can throw a [DecodeErr]
+    pub fn from_string(input: &str) -> Result<Vec<Meld>, DecodeErr> {
+        let input = input.as_bytes();
+        let mut position = 0;
+        let mut melds = vec![];
+
+        while position < input.len() {
+            melds.push(Meld::decode(input, &mut position)?);
+        }
+
+        Ok(melds)
+    }
+
+    pub(crate) fn encode(&self, out: &mut Vec<u8>) {
+        let seat = match self.claim.map(|claim| claim.from) {
+            None => 0,
+            Some(Seat::Left) => 1,
+            Some(Seat::Across) => 2,
+            Some(Seat::Right) => 3,
+        };
+        out.push(ALPHABET[self.kind.to_byte() as usize]);
+        out.push(ALPHABET[seat]);
+
+        let mut tiles = self.tiles.clone();
+        if let Some(claim) = self.claim {
+            let index = tiles.iter().position(|tile| *tile == claim.tile).unwrap();
+            tiles[..=index].rotate_right(1);
+        }
+        out.extend(tiles.iter().map(|tile| ALPHABET[tile.to_byte() as usize]));
+    }
+
+    /// Reads a single meld starting at `position`, leaving `position` just after it
+    pub(crate) fn decode(input: &[u8], position: &mut usize) -> Result<Meld, DecodeErr> {
+        let mut next = |expected: &'static str| {
+            let byte = *input.get(*position).ok_or(DecodeErr::UnexpectedEnd {
+                position: *position,
+            })?;
+            let value = value_of(byte);
+            let err = DecodeErr::InvalidCharacter {
+                byte,
+                position: *position,
+                expected,
+            };
+            *position += 1;
+
+            value.ok_or(err).map(|value| (value, err))
+        };
+
+        let (marker, err) = next(EXPECTED_MARKER)?;
+        let kind = MeldKind::from_byte(marker).ok_or(err)?;
+
+        let (seat, err) = next(EXPECTED_SEAT)?;
+        let from = match seat {
+            0 => None,
+            1 => Some(Seat::Left),
+            2 => Some(Seat::Across),
+            3 => Some(Seat::Right),
+            _ => return Err(err),
+        };
+
+        let mut tiles = vec![];
+        for _ in 0..kind.tile_count() {
+            let (byte, err) = next(EXPECTED_ALPHABET)?;
+            tiles.push(INDEX[ALPHABET[byte as usize] as usize].ok_or(err)?);
+        }
+
+        match from {
+            Some(from) => Meld::called(kind, tiles.clone(), tiles[0], from),
+            None => Meld::new(kind, tiles),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn tiles(hand: &str) -> Vec<Suit> {
+        Suit::from_mpsz(hand).unwrap()
+    }
+
+    #[test]
+    fn validates_chi() {
+        assert!(Meld::new(MeldKind::Chi, tiles("789s")).is_ok());
+        assert!(Meld::new(MeldKind::Chi, tiles("123z")).is_err());
+        assert!(Meld::new(MeldKind::Chi, tiles("135m")).is_err());
+        assert!(Meld::new(MeldKind::Chi, tiles("1234m")).is_err());
+        assert_eq!(
+            Meld::new(MeldKind::Chi, tiles("9m12p")),
+            Err(DecodeErr::InvalidMeld {
+                kind: MeldKind::Chi
+            })
+        );
+    }
+
+    #[test]
+    fn validates_triplets_and_quads() {
+        assert!(Meld::new(MeldKind::Pon, tiles("505p")).is_ok());
+        assert!(Meld::new(MeldKind::Pon, tiles("556p")).is_err());
+        assert!(Meld::new(MeldKind::OpenKan, tiles("555p")).is_err());
+        assert!(Meld::new(MeldKind::AddedKan, tiles("2222z")).is_ok());
+        assert_eq!(
+            Meld::new(MeldKind::Pon, vec![Suit::Dots(0); 3]),
+            Err(DecodeErr::InvalidTile {
+                tile: Suit::Dots(0)
+            })
+        );
+    }
+
+    #[test]
+    fn upgrades_only_pon() {
+        let pon = Meld::called(
+            MeldKind::Pon,
+            tiles("666z"),
+            Suit::Dragon(Dragon::Green),
+            Seat::Right,
+        )
+        .unwrap();
+        let kan = pon.upgrade(Suit::Dragon(Dragon::Green)).unwrap();
+
+        assert!(kan.is_kan());
+        assert_eq!(kan.claim(), pon.claim());
+        assert!(pon.upgrade(Suit::Dragon(Dragon::Red)).is_err());
+        assert!(kan.upgrade(Suit::Dragon(Dragon::Green)).is_err());
+    }
+
+    #[test]
+    fn round_trips_through_text() {
+        let melds = vec![
+            Meld::called(
+                MeldKind::Chi,
+                tiles("406m"),
+                Suit::Characters(RED_FIVE),
+                Seat::Left,
+            )
+            .unwrap(),
+            Meld::called(
+                MeldKind::OpenKan,
+                tiles("9999s"),
+                Suit::Bamboo(9),
+                Seat::Right,
+            )
+            .unwrap(),
+            Meld::new(MeldKind::Pon, tiles("111z")).unwrap(),
+        ];
+        let text = Meld::to_string(&melds);
+
+        assert!(text.starts_with("BB602"));
+        assert_eq!(Meld::from_string(&text), Ok(melds));
+    }
+
+    #[test]
+    fn reports_bad_meld_text() {
+        assert_eq!(
+            Meld::from_string("CAcc"),
+            Err(DecodeErr::UnexpectedEnd { position: 4 })
+        );
+        assert_eq!(
+            Meld::from_string("GAccc"),
+            Err(DecodeErr::InvalidCharacter {
+                byte: b'G',
+                position: 0,
+                expected: EXPECTED_MARKER,
+            })
+        );
+        assert_eq!(
+            Meld::from_string("CEccc"),
+            Err(DecodeErr::InvalidCharacter {
+                byte: b'E',
+                position: 1,
+                expected: EXPECTED_SEAT,
+            })
+        );
+        assert_eq!(
+            Meld::from_string("BDRST"),
+            Err(DecodeErr::InvalidMeld {
+                kind: MeldKind::Chi
+            })
+        );
+    }
+}
@@ -0,0 +1,799 @@
+//! The mjai protocol, newline separated JSON messages between a game server and
+//! the bots playing on it.
+//!
+//! Tiles are short strings: `1m`–`9m`, `1p`–`9p` and `1s`–`9s` for the numbered
+//! suits with `5mr`, `5pr` and `5sr` for the red fives, `E`, `S`, `W` and `N` for the
+//! winds, and `P`, `F` and `C` for the white, green and red dragons. A tile the
+//! receiver isn't allowed to see is `?`.
+
+use crate::json::Json;
+use crate::*;
+
+/// A tile hidden from the receiver
+const UNKNOWN: &str = "?";
+
+const WINDS: [(Wind, &str); 4] =
+    [(Wind::East, "E"), (Wind::South, "S"), (Wind::West, "W"), (Wind::North, "N")];
+const DRAGONS: [(Dragon, &str); 3] =
+    [(Dragon::White, "P"), (Dragon::Green, "F"), (Dragon::Red, "C")];
+
+impl Suit {
+    /// The tile as mjai writes it, tiles that aren't [valid](Suit::is_valid) have none
+    ///
+    /// ```
+    /// # use mahjong_encoding::*;
+    /// assert_eq!(Suit::Characters(3).to_mjai(), Some("3m".to_string()));
+    /// assert_eq!(Suit::Bamboo(RED_FIVE).to_mjai(), Some("5sr".to_string()));
+    /// assert_eq!(Suit::Dragon(Dragon::White).to_mjai(), Some("P".to_string()));
+    /// assert_eq!(Suit::Dots(0).to_mjai(), None);
+    /// ```
+    pub fn to_mjai(&self) -> Option<String> {
+        if !self.is_valid() {
+            return None;
+        }
+
+        let (rank, suit) = match *self {
+            Suit::Wind(wind) => {
+                return WINDS
+                    .iter()
+                    .find(|(other, _)| *other == wind)
+                    .map(|(_, name)| name.to_string())
+            }
+            Suit::Dragon(dragon) => {
+                return DRAGONS
+                    .iter()
+                    .find(|(other, _)| *other == dragon)
+                    .map(|(_, name)| name.to_string())
+            }
+            Suit::Characters(n) => (n, 'm'),
+            Suit::Dots(n) => (n, 'p'),
+            Suit::Bamboo(n) => (n, 's'),
+        };
+
+        Some(match rank {
+            RED_FIVE => format!("5{suit}r"),
+            rank => format!("{rank}{suit}"),
+        })
+    }
+
+    /// The tile for an mjai tile string, `None` for `?` and anything that isn't a tile
+    ///
+    /// ```
+    /// # use mahjong_encoding::*;
+    /// assert_eq!(Suit::from_mjai("5pr"), Some(Suit::Dots(RED_FIVE)));
+    /// assert_eq!(Suit::from_mjai("C"), Some(Suit::Dragon(Dragon::Red)));
+    /// assert_eq!(Suit::from_mjai("?"), None);
+    /// ```
+    pub fn from_mjai(tile: &str) -> Option<Suit> {
+        if let Some((wind, _)) = WINDS.iter().find(|(_, name)| *name == tile) {
+            return Some(Suit::Wind(*wind));
+        }
+        if let Some((dragon, _)) = DRAGONS.iter().find(|(_, name)| *name == tile) {
+            return Some(Suit::Dragon(*dragon));
+        }
+
+        let (rank, suit) = match tile.as_bytes() {
+            [b'5', suit, b'r'] => (RED_FIVE, *suit),
+            [rank @ b'1'..=b'9', suit] => (rank - b'0', *suit),
+            _ => return None,
+        };
+
+        match suit {
+            b'm' => Some(Suit::Characters(rank)),
+            b'p' => Some(Suit::Dots(rank)),
+            b's' => Some(Suit::Bamboo(rank)),
+            _ => None,
+        }
+    }
+}
+
+/// A single mjai message, as sent by the server or a bot. Players are numbered by
+/// seat, `0`–`3`, and tiles the receiver can't see are `None`.
+///
+/// ```
+/// # use mahjong_encoding::*;
+/// let message = MjaiMessage::from_json(
+///     r#"{"type":"chi","actor":1,"target":0,"pai":"4m","consumed":["5mr","6m"]}"#,
+/// )
+/// .unwrap();
+/// let meld = message.meld().unwrap().unwrap();
+///
+/// assert_eq!(meld.kind(), MeldKind::Chi);
+/// assert_eq!(Suit::to_mpsz(meld.tiles()), "406m");
+/// assert_eq!(MjaiMessage::from_json(&message.to_json().unwrap()), Ok(message));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MjaiMessage {
+    /// The game is starting
+    StartGame {
+        /// The players' names, in seat order
+        names: Vec<String>,
+        /// The receiver's own seat, if the server says
+        id: Option<usize>,
+    },
+    /// 局 _(kyoku)_, a round is starting
+    StartKyoku {
+        /// 場風 _(bakaze)_, the wind of the round
+        round_wind: Wind,
+        /// Which round of that wind this is, from `1`
+        number: u8,
+        /// 本場 _(honba)_, repeat counters on the table
+        honba: u8,
+        /// 供託 _(kyoutaku)_, riichi sticks left on the table
+        riichi_sticks: u8,
+        /// The seat of the dealer
+        dealer: usize,
+        /// The first ドラ表示牌 _(dora hyoujihai)_
+        dora_indicator: Suit,
+        /// Each player's points, if the server sends them
+        scores: Option<[i32; 4]>,
+        /// Each player's starting hand
+        hands: [Vec<Option<Suit>>; 4],
+    },
+    /// A player drew a tile
+    Tsumo {
+        /// Who drew
+        actor: usize,
+        /// The tile drawn
+        tile: Option<Suit>,
+    },
+    /// A player discarded a tile
+    Dahai {
+        /// Who discarded
+        actor: usize,
+        /// The tile discarded
+        tile: Suit,
+        /// ツモ切り _(tsumogiri)_, whether it was the tile just drawn
+        tsumogiri: bool,
+    },
+    /// A player called chi
+    Chi {
+        /// Who called
+        actor: usize,
+        /// Who discarded the called tile
+        target: usize,
+        /// The called tile
+        tile: Suit,
+        /// The tiles from the caller's hand
+        consumed: Vec<Suit>,
+    },
+    /// A player called pon
+    Pon {
+        /// Who called
+        actor: usize,
+        /// Who discarded the called tile
+        target: usize,
+        /// The called tile
+        tile: Suit,
+        /// The tiles from the caller's hand
+        consumed: Vec<Suit>,
+    },
+    /// 大明槓 _(daiminkan)_, a player called an open kan
+    Daiminkan {
+        /// Who called
+        actor: usize,
+        /// Who discarded the called tile
+        target: usize,
+        /// The called tile
+        tile: Suit,
+        /// The tiles from the caller's hand
+        consumed: Vec<Suit>,
+    },
+    /// 加槓 _(kakan)_, a player added a tile to their pon
+    Kakan {
+        /// Who declared
+        actor: usize,
+        /// The added tile
+        tile: Suit,
+        /// The tiles of the pon
+        consumed: Vec<Suit>,
+    },
+    /// 暗槓 _(ankan)_, a player declared a closed kan
+    Ankan {
+        /// Who declared
+        actor: usize,
+        /// The four tiles
+        consumed: Vec<Suit>,
+    },
+    /// A new ドラ表示牌 _(dora hyoujihai)_ was revealed after a kan
+    Dora {
+        /// The new indicator
+        dora_indicator: Suit,
+    },
+    /// A player declared riichi, their next discard is the riichi tile
+    Reach {
+        /// Who declared
+        actor: usize,
+    },
+    /// A riichi discard went through and the stick was put on the table
+    ReachAccepted {
+        /// Who declared
+        actor: usize,
+    },
+    /// 和了 _(agari)_, a player won
+    Hora {
+        /// Who won
+        actor: usize,
+        /// Who dealt in, the same as `actor` for a tsumo
+        target: usize,
+        /// The winning tile, if the server sends it
+        tile: Option<Suit>,
+        /// 裏ドラ表示牌 _(ura dora hyoujihai)_, if any were shown
+        ura_dora_indicators: Vec<Suit>,
+        /// How many points each player gained or lost, if the server sends them
+        deltas: Option<[i32; 4]>,
+    },
+    /// 流局 _(ryuukyoku)_, the round ended without a winner
+    Ryukyoku {
+        /// How many points each player gained or lost, if the server sends them
+        deltas: Option<[i32; 4]>,
+    },
+    /// The round is over
+    EndKyoku,
+    /// The game is over
+    EndGame,
+    /// A bot passing on a call or win it was offered
+    None,
+}
+
+impl MjaiMessage {
+    /// Reads a single message. Fields the crate doesn't know about are skipped.
+    /// Can throw a [DecodeErr]
+    pub fn from_json(input: &str) -> Result<MjaiMessage, DecodeErr> {
+        let json = Json::parse(input)?;
+        let kind = json
+            .get("type")
+            .and_then(Json::as_str)
+            .ok_or(DecodeErr::InvalidField { field: "type" })?;
+
+        let actor = || seat(&json, "actor");
+        let target = || seat(&json, "target");
+        let tile = || tile(json.get("pai"), "pai");
+        let consumed = || tiles(json.get("consumed"), "consumed");
+        let deltas = || optional(json.get("deltas"), "deltas", score_list);
+
+        Ok(match kind {
+            "start_game" => MjaiMessage::StartGame {
+                names: optional(json.get("names"), "names", |names| {
+                    array(Some(names), "names")?
+                        .iter()
+                        .map(|name| name.as_str().map(str::to_string))
+                        .collect::<Option<_>>()
+                        .ok_or(DecodeErr::InvalidField { field: "names" })
+                })?
+                .unwrap_or_default(),
+                id: optional(json.get("id"), "id", |_| seat(&json, "id"))?,
+            },
+            "start_kyoku" => {
+                let bakaze = json.get("bakaze").and_then(Json::as_str);
+                let hands = array(json.get("tehais"), "tehais")?;
+                let hand = |seat: usize| {
+                    array(hands.get(seat), "tehais")?
+                        .iter()
+                        .map(|tile| match tile.as_str() {
+                            Some(UNKNOWN) => Ok(None),
+                            _ => self::tile(Some(tile), "tehais").map(Some),
+                        })
+                        .collect::<Result<Vec<_>, _>>()
+                };
+
+                MjaiMessage::StartKyoku {
+                    round_wind: WINDS
+                        .iter()
+                        .find(|(_, name)| Some(*name) == bakaze)
+                        .map(|(wind, _)| *wind)
+                        .ok_or(DecodeErr::InvalidField { field: "bakaze" })?,
+                    number: small(&json, "kyoku")?,
+                    honba: small(&json, "honba")?,
+                    riichi_sticks: small(&json, "kyotaku")?,
+                    dealer: seat(&json, "oya")?,
+                    dora_indicator: self::tile(json.get("dora_marker"), "dora_marker")?,
+                    scores: optional(json.get("scores"), "scores", score_list)?,
+                    hands: [hand(0)?, hand(1)?, hand(2)?, hand(3)?],
+                }
+            }
+            "tsumo" => MjaiMessage::Tsumo {
+                actor: actor()?,
+                tile: match json.get("pai").and_then(Json::as_str) {
+                    Some(UNKNOWN) => None,
+                    _ => Some(tile()?),
+                },
+            },
+            "dahai" => MjaiMessage::Dahai {
+                actor: actor()?,
+                tile: tile()?,
+                tsumogiri: match json.get("tsumogiri") {
+                    Some(Json::Bool(tsumogiri)) => *tsumogiri,
+                    None => false,
+                    Some(_) => return Err(DecodeErr::InvalidField { field: "tsumogiri" }),
+                },
+            },
+            "chi" => MjaiMessage::Chi {
+                actor: actor()?,
+                target: target()?,
+                tile: tile()?,
+                consumed: consumed()?,
+            },
+            "pon" => MjaiMessage::Pon {
+                actor: actor()?,
+                target: target()?,
+                tile: tile()?,
+                consumed: consumed()?,
+            },
+            "daiminkan" => MjaiMessage::Daiminkan {
+                actor: actor()?,
+                target: target()?,
+                tile: tile()?,
+                consumed: consumed()?,
+            },
+            "kakan" => MjaiMessage::Kakan {
+                actor: actor()?,
+                tile: tile()?,
+                consumed: consumed()?,
+            },
+            "ankan" => MjaiMessage::Ankan {
+                actor: actor()?,
+                consumed: consumed()?,
+            },
+            "dora" => MjaiMessage::Dora {
+                dora_indicator: self::tile(json.get("dora_marker"), "dora_marker")?,
+            },
+            "reach" => MjaiMessage::Reach { actor: actor()? },
+            "reach_accepted" => MjaiMessage::ReachAccepted { actor: actor()? },
+            "hora" => MjaiMessage::Hora {
+                actor: actor()?,
+                target: target()?,
+                tile: optional(json.get("pai"), "pai", |_| tile())?,
+                ura_dora_indicators: optional(json.get("ura_markers"), "ura_markers", |json| {
+                    tiles(Some(json), "ura_markers")
+                })?
+                .unwrap_or_default(),
+                deltas: deltas()?,
+            },
+            "ryukyoku" => MjaiMessage::Ryukyoku { deltas: deltas()? },
+            "end_kyoku" => MjaiMessage::EndKyoku,
+            "end_game" => MjaiMessage::EndGame,
+            "none" => MjaiMessage::None,
+            _ => return Err(DecodeErr::InvalidField { field: "type" }),
+        })
+    }
+
+    /// Writes the message as a single line of JSON. Can throw a [DecodeErr] if a tile
+    /// isn't [valid](Suit::is_valid), since it couldn't be read back
+    pub fn to_json(&self) -> Result<String, DecodeErr> {
+        let seat = |seat: usize| Json::from(seat as i64);
+        let tile = |tile: &Suit| {
+            tile.to_mjai()
+                .map(|name| Json::from(name.as_str()))
+                .ok_or(DecodeErr::InvalidTile { tile: *tile })
+        };
+        let tiles = |tiles: &[Suit]| {
+            tiles
+                .iter()
+                .map(tile)
+                .collect::<Result<_, _>>()
+                .map(Json::Array)
+        };
+        let hidden = |maybe: &Option<Suit>| maybe.as_ref().map_or(Ok(UNKNOWN.into()), tile);
+        let scores = |scores: &[i32; 4]| Json::Array(scores.map(|s| (s as i64).into()).to_vec());
+
+        let (kind, fields): (&str, Vec<(&str, Json)>) = match self {
+            MjaiMessage::StartGame { names, id } => {
+                let mut fields = vec![(
+                    "names",
+                    Json::Array(names.iter().map(|name| name.as_str().into()).collect()),
+                )];
+                fields.extend(id.map(|id| ("id", seat(id))));
+                ("start_game", fields)
+            }
+            MjaiMessage::StartKyoku {
+                round_wind,
+                number,
+                honba,
+                riichi_sticks,
+                dealer,
+                dora_indicator,
+                scores: points,
+                hands,
+            } => {
+                let wind = WINDS.iter().find(|(wind, _)| wind == round_wind).unwrap();
+                let mut fields = vec![
+                    ("bakaze", wind.1.into()),
+                    ("kyoku", (*number as i64).into()),
+                    ("honba", (*honba as i64).into()),
+                    ("kyotaku", (*riichi_sticks as i64).into()),
+                    ("oya", seat(*dealer)),
+                    ("dora_marker", tile(dora_indicator)?),
+                ];
+                fields.extend(points.as_ref().map(|points| ("scores", scores(points))));
+                let hands = hands
+                    .iter()
+                    .map(|hand| {
+                        hand.iter()
+                            .map(hidden)
+                            .collect::<Result<_, _>>()
+                            .map(Json::Array)
+                    })
+                    .collect::<Result<_, _>>()?;
+                fields.push(("tehais", Json::Array(hands)));
+                ("start_kyoku", fields)
+            }
+            MjaiMessage::Tsumo { actor, tile } => (
+                "tsumo",
+                vec![("actor", seat(*actor)), ("pai", hidden(tile)?)],
+            ),
+            MjaiMessage::Dahai {
+                actor,
+                tile: discarded,
+                tsumogiri,
+            } => (
+                "dahai",
+                vec![
+                    ("actor", seat(*actor)),
+                    ("pai", tile(discarded)?),
+                    ("tsumogiri", Json::Bool(*tsumogiri)),
+                ],
+            ),
+            MjaiMessage::Chi {
+                actor,
+                target,
+                tile: called,
+                consumed,
+            }
+            | MjaiMessage::Pon {
+                actor,
+                target,
+                tile: called,
+                consumed,
+            }
+            | MjaiMessage::Daiminkan {
+                actor,
+                target,
+                tile: called,
+                consumed,
+            } => (
+                self.kind(),
+                vec![
+                    ("actor", seat(*actor)),
+                    ("target", seat(*target)),
+                    ("pai", tile(called)?),
+                    ("consumed", tiles(consumed)?),
+                ],
+            ),
+            MjaiMessage::Kakan {
+                actor,
+                tile: added,
+                consumed,
+            } => (
+                "kakan",
+                vec![
+                    ("actor", seat(*actor)),
+                    ("pai", tile(added)?),
+                    ("consumed", tiles(consumed)?),
+                ],
+            ),
+            MjaiMessage::Ankan { actor, consumed } => (
+                "ankan",
+                vec![("actor", seat(*actor)), ("consumed", tiles(consumed)?)],
+            ),
+            MjaiMessage::Dora { dora_indicator } => {
+                ("dora", vec![("dora_marker", tile(dora_indicator)?)])
+            }
+            MjaiMessage::Reach { actor } | MjaiMessage::ReachAccepted { actor } => {
+                (self.kind(), vec![("actor", seat(*actor))])
+            }
+            MjaiMessage::Hora {
+                actor,
+                target,
+                tile: winning,
+                ura_dora_indicators,
+                deltas,
+            } => {
+                let mut fields = vec![("actor", seat(*actor)), ("target", seat(*target))];
+                if let Some(winning) = winning {
+                    fields.push(("pai", tile(winning)?));
+                }
+                if !ura_dora_indicators.is_empty() {
+                    fields.push(("ura_markers", tiles(ura_dora_indicators)?));
+                }
+                fields.extend(deltas.as_ref().map(|deltas| ("deltas", scores(deltas))));
+                ("hora", fields)
+            }
+            MjaiMessage::Ryukyoku { deltas } => (
+                "ryukyoku",
+                deltas
+                    .as_ref()
+                    .map(|deltas| ("deltas", scores(deltas)))
+                    .into_iter()
+                    .collect(),
+            ),
+            MjaiMessage::EndKyoku | MjaiMessage::EndGame | MjaiMessage::None => {
+                (self.kind(), vec![])
+            }
+        };
+
+        let mut object = vec![("type".to_string(), kind.into())];
+        object.extend(
+            fields
+                .into_iter()
+                .map(|(key, value)| (key.to_string(), value)),
+        );
+        Ok(Json::Object(object).to_string())
+    }
+
+    /// The message's `type`, e.g. `start_kyoku`
+    pub fn kind(&self) -> &'static str {
+        match self {
+            MjaiMessage::StartGame { .. } => "start_game",
+            MjaiMessage::StartKyoku { .. } => "start_kyoku",
+            MjaiMessage::Tsumo { .. } => "tsumo",
+            MjaiMessage::Dahai { .. } => "dahai",
+            MjaiMessage::Chi { .. } => "chi",
+            MjaiMessage::Pon { .. } => "pon",
+            MjaiMessage::Daiminkan { .. } => "daiminkan",
+            MjaiMessage::Kakan { .. } => "kakan",
+            MjaiMessage::Ankan { .. } => "ankan",
+            MjaiMessage::Dora { .. } => "dora",
+            MjaiMessage::Reach { .. } => "reach",
+            MjaiMessage::ReachAccepted { .. } => "reach_accepted",
+            MjaiMessage::Hora { .. } => "hora",
+            MjaiMessage::Ryukyoku { .. } => "ryukyoku",
+            MjaiMessage::EndKyoku => "end_kyoku",
+            MjaiMessage::EndGame => "end_game",
+            MjaiMessage::None => "none",
+        }
+    }
+
+    /// The meld a call or kan makes, `None` for any other message.
+    /// Can throw a [DecodeErr] if the tiles don't make a meld
+    pub fn meld(&self) -> Result<Option<Meld>, DecodeErr> {
+        let called = |kind, actor: &usize, target: &usize, tile: &Suit, consumed: &[Suit]| {
+            let from = match (target + 4 - actor) % 4 {
+                1 => Seat::Right,
+                2 => Seat::Across,
+                3 => Seat::Left,
+                _ => return Err(DecodeErr::InvalidMeld { kind }),
+            };
+            let mut tiles = consumed.to_vec();
+            tiles.push(*tile);
+
+            Meld::called(kind, tiles, *tile, from)
+        };
+
+        match self {
+            MjaiMessage::Chi {
+                actor,
+                target,
+                tile,
+                consumed,
+            } => called(MeldKind::Chi, actor, target, tile, consumed),
+            MjaiMessage::Pon {
+                actor,
+                target,
+                tile,
+                consumed,
+            } => called(MeldKind::Pon, actor, target, tile, consumed),
+            MjaiMessage::Daiminkan {
+                actor,
+                target,
+                tile,
+                consumed,
+            } => called(MeldKind::OpenKan, actor, target, tile, consumed),
+            MjaiMessage::Kakan { tile, consumed, .. } => {
+                let mut tiles = consumed.clone();
+                tiles.push(*tile);
+                Meld::new(MeldKind::AddedKan, tiles)
+            }
+            MjaiMessage::Ankan { consumed, .. } => Meld::new(MeldKind::ClosedKan, consumed.clone()),
+            _ => return Ok(None),
+        }
+        .map(Some)
+    }
+}
+
+fn tile(json: Option<&Json>, field: &'static str) -> Result<Suit, DecodeErr> {
+    json.and_then(Json::as_str)
+        .and_then(Suit::from_mjai)
+        .ok_or(DecodeErr::InvalidField { field })
+}
+
+fn tiles(json: Option<&Json>, field: &'static str) -> Result<Vec<Suit>, DecodeErr> {
+    array(json, field)?
+        .iter()
+        .map(|value| tile(Some(value), field))
+        .collect()
+}
+
+fn array<'a>(json: Option<&'a Json>, field: &'static str) -> Result<&'a [Json], DecodeErr> {
+    json.and_then(Json::as_array)
+        .ok_or(DecodeErr::InvalidField { field })
+}
+
+fn seat(json: &Json, field: &'static str) -> Result<usize, DecodeErr> {
+    json.get(field)
+        .and_then(Json::as_i64)
+        .filter(|seat| (0..4).contains(seat))
+        .map(|seat| seat as usize)
+        .ok_or(DecodeErr::InvalidField { field })
+}
+
+fn small(json: &Json, field: &'static str) -> Result<u8, DecodeErr> {
+    json.get(field)
+        .and_then(Json::as_i64)
+        .and_then(|value| u8::try_from(value).ok())
+        .ok_or(DecodeErr::InvalidField { field })
+}
+
+fn score_list(json: &Json) -> Result<[i32; 4], DecodeErr> {
+    let invalid = DecodeErr::InvalidField { field: "scores" };
+
+    array(Some(json), "scores")?
+        .iter()
+        .map(|score| {
+            score
+                .as_i64()
+                .and_then(|score| i32::try_from(score).ok())
+                .ok_or(invalid)
+        })
+        .collect::<Result<Vec<_>, _>>()?
+        .try_into()
+        .map_err(|_| invalid)
+}
+
+/// Reads a field that may be missing or `null`
+fn optional<T>(
+    json: Option<&Json>,
+    field: &'static str,
+    read: impl FnOnce(&Json) -> Result<T, DecodeErr>,
+) -> Result<Option<T>, DecodeErr> {
+    match json {
+        None | Some(Json::Null) => Ok(None),
+        Some(json) => read(json)
+            .map(Some)
+            .map_err(|_| DecodeErr::InvalidField { field }),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn round_trip(input: &str) -> MjaiMessage {
+        let message = MjaiMessage::from_json(input).unwrap();
+        assert_eq!(message.to_json(), Ok(input.to_string()));
+        message
+    }
+
+    #[test]
+    fn round_trips_every_tile() {
+        let mut tiles = (0..TILE_KINDS)
+            .filter_map(Suit::from_index)
+            .collect::<Vec<_>>();
+        tiles.extend(Suit::from_mpsz("0m0p0s").unwrap());
+
+        for tile in tiles {
+            assert_eq!(Suit::from_mjai(&tile.to_mjai().unwrap()), Some(tile));
+        }
+        for name in ["0m", "5zr", "6mr", "m", "EE", ""] {
+            assert_eq!(Suit::from_mjai(name), None);
+        }
+    }
+
+    #[test]
+    fn round_trips_a_round() {
+        let start = round_trip(
+            r#"{"type":"start_kyoku","bakaze":"S","kyoku":2,"honba":1,"kyotaku":0,"oya":1,"dora_marker":"5sr","scores":[25000,25000,25000,25000],"tehais":[["1m","P"],["?","?"],["?","?"],["?","?"]]}"#,
+        );
+        let MjaiMessage::StartKyoku { hands, .. } = &start else {
+            panic!("expected start_kyoku");
+        };
+        assert_eq!(
+            hands[0],
+            [Some(Suit::Characters(1)), Some(Suit::Dragon(Dragon::White))]
+        );
+        assert_eq!(hands[1], [None, None]);
+
+        assert_eq!(
+            round_trip(r#"{"type":"tsumo","actor":2,"pai":"?"}"#),
+            MjaiMessage::Tsumo {
+                actor: 2,
+                tile: None
+            }
+        );
+        assert_eq!(
+            round_trip(r#"{"type":"dahai","actor":0,"pai":"C","tsumogiri":true}"#),
+            MjaiMessage::Dahai {
+                actor: 0,
+                tile: Suit::Dragon(Dragon::Red),
+                tsumogiri: true
+            }
+        );
+        round_trip(r#"{"type":"start_game","names":["a","b","c","d"],"id":3}"#);
+        round_trip(r#"{"type":"reach","actor":1}"#);
+        round_trip(r#"{"type":"reach_accepted","actor":1}"#);
+        round_trip(r#"{"type":"dora","dora_marker":"N"}"#);
+        round_trip(
+            r#"{"type":"hora","actor":1,"target":3,"pai":"9p","ura_markers":["1s"],"deltas":[0,8300,0,-8300]}"#,
+        );
+        round_trip(r#"{"type":"ryukyoku","deltas":[1500,-1500,1500,-1500]}"#);
+        round_trip(r#"{"type":"end_kyoku"}"#);
+        round_trip(r#"{"type":"end_game"}"#);
+        round_trip(r#"{"type":"none"}"#);
+    }
+
+    #[test]
+    fn reads_melds() {
+        let meld = |input: &str| {
+            MjaiMessage::from_json(input)
+                .unwrap()
+                .meld()
+                .unwrap()
+                .unwrap()
+        };
+
+        let pon = meld(r#"{"type":"pon","actor":0,"target":2,"pai":"F","consumed":["F","F"]}"#);
+        assert_eq!(pon.kind(), MeldKind::Pon);
+        assert_eq!(pon.claim().unwrap().from, Seat::Across);
+
+        let kan = meld(
+            r#"{"type":"daiminkan","actor":3,"target":0,"pai":"5p","consumed":["5pr","5p","5p"]}"#,
+        );
+        assert_eq!(kan.kind(), MeldKind::OpenKan);
+        assert_eq!(kan.claim().unwrap().from, Seat::Right);
+
+        let added = meld(r#"{"type":"kakan","actor":0,"pai":"E","consumed":["E","E","E"]}"#);
+        assert_eq!(added.kind(), MeldKind::AddedKan);
+
+        let closed = meld(r#"{"type":"ankan","actor":0,"consumed":["9s","9s","9s","9s"]}"#);
+        assert_eq!(closed.kind(), MeldKind::ClosedKan);
+
+        assert_eq!(MjaiMessage::EndGame.meld(), Ok(None));
+        assert_eq!(
+            MjaiMessage::from_json(
+                r#"{"type":"chi","actor":0,"target":2,"pai":"3m","consumed":["4m","5m"]}"#
+            )
+            .unwrap()
+            .meld(),
+            Err(DecodeErr::InvalidMeld {
+                kind: MeldKind::Chi
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_bad_messages() {
+        assert_eq!(
+            MjaiMessage::from_json(r#"{"type":"shuffle"}"#),
+            Err(DecodeErr::InvalidField { field: "type" })
+        );
+        assert_eq!(
+            MjaiMessage::from_json(r#"{"type":"dahai","actor":0,"pai":"0m"}"#),
+            Err(DecodeErr::InvalidField { field: "pai" })
+        );
+        assert_eq!(
+            MjaiMessage::from_json(r#"{"type":"reach","actor":4}"#),
+            Err(DecodeErr::InvalidField { field: "actor" })
+        );
+        assert_eq!(
+            MjaiMessage::from_json(r#"{"type":"ryukyoku","deltas":[0,0,0,4294967296]}"#),
+            Err(DecodeErr::InvalidField { field: "deltas" })
+        );
+        assert!(MjaiMessage::from_json("[]").is_err());
+    }
+
+    #[test]
+    fn refuses_to_write_invalid_tiles() {
+        let message = MjaiMessage::Dahai {
+            actor: 0,
+            tile: Suit::Dots(0),
+            tsumogiri: false,
+        };
+
+        assert_eq!(
+            message.to_json(),
+            Err(DecodeErr::InvalidTile {
+                tile: Suit::Dots(0)
+            })
+        );
+    }
+}
@@ -0,0 +1,643 @@
+//! Tenhou's `.mjlog` replays, XML logs where every tile is one of the 136 physical
+//! tiles, numbered `0`–`135`.
+//!
+//! Tile `id / 4` is the [tile index](Suit::index) and ids `16`, `52` and `88` are the
+//! red fives, unless the game was played without them.
+
+use crate::xml::Element;
+use crate::*;
+
+/// Bit of the `GO` element's `type` that marks a game without red fives
+const NO_RED_FIVES: u32 = 0x02;
+
+const WINDS: [Wind; 4] = [Wind::East, Wind::South, Wind::West, Wind::North];
+
+impl Suit {
+    /// The tile for one of Tenhou's 136 tile ids, where `16`, `52` and `88` are the
+    /// red fives
+    ///
+    /// ```
+    /// # use mahjong_encoding::*;
+    /// assert_eq!(Suit::from_tenhou_id(0), Some(Suit::Characters(1)));
+    /// assert_eq!(Suit::from_tenhou_id(52), Some(Suit::Dots(RED_FIVE)));
+    /// assert_eq!(Suit::from_tenhou_id(53), Some(Suit::Dots(5)));
+    /// assert_eq!(Suit::from_tenhou_id(135), Some(Suit::Dragon(Dragon::Red)));
+    /// assert_eq!(Suit::from_tenhou_id(136), None);
+    /// ```
+    pub fn from_tenhou_id(id: u8) -> Option<Suit> {
+        let tile = Suit::from_index(id as usize / 4)?;
+
+        Some(match id {
+            16 | 52 | 88 => tile.to_red(),
+            _ => tile,
+        })
+    }
+}
+
+/// A whole game read from an `.mjlog`, see [Replay::from_mjlog]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Replay {
+    /// The players' names, in seat order from the first dealer
+    pub players: Vec<String>,
+    /// Whether the game was played with red fives
+    pub red_fives: bool,
+    /// Every round, in order
+    pub rounds: Vec<Round>,
+}
+
+/// 局 _(kyoku)_, a single round of a [Replay]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Round {
+    /// 場風 _(bakaze)_, the wind of the round
+    pub round_wind: Wind,
+    /// Which round of that wind this is, from `1`
+    pub number: u8,
+    /// 本場 _(honba)_, repeat counters on the table
+    pub honba: u8,
+    /// 供託 _(kyoutaku)_, riichi sticks left on the table
+    pub riichi_sticks: u8,
+    /// The seat of the dealer
+    pub dealer: usize,
+    /// The dice that were rolled to break the wall
+    pub dice: [u8; 2],
+    /// The first ドラ表示牌 _(dora hyoujihai)_
+    pub dora_indicator: Suit,
+    /// Each player's points at the start of the round
+    pub scores: [i32; 4],
+    /// Each player's starting hand
+    pub hands: [Vec<Suit>; 4],
+    /// What happened during the round, in order
+    pub events: Vec<Event>,
+}
+
+/// Something that happened during a [Round]. Players are numbered by seat, `0`–`3`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    /// A player drew a tile
+    Draw {
+        /// Who drew
+        who: usize,
+        /// The tile drawn
+        tile: Suit,
+    },
+    /// A player discarded a tile
+    Discard {
+        /// Who discarded
+        who: usize,
+        /// The tile discarded
+        tile: Suit,
+    },
+    /// A player called a meld or declared a kan
+    Call {
+        /// Who called
+        who: usize,
+        /// The meld made
+        meld: Meld,
+    },
+    /// 北抜き _(kita nuki)_, a north set aside in three player games
+    Nuki {
+        /// Who set it aside
+        who: usize,
+    },
+    /// A player declared riichi, their next discard is the riichi tile
+    Riichi {
+        /// Who declared
+        who: usize,
+    },
+    /// A riichi discard went through and the stick was put on the table
+    RiichiAccepted {
+        /// Who declared
+        who: usize,
+    },
+    /// A new ドラ表示牌 _(dora hyoujihai)_ was revealed after a kan
+    Dora {
+        /// The new indicator
+        indicator: Suit,
+    },
+    /// 和了 _(agari)_, someone won
+    Win(Agari),
+    /// 流局 _(ryuukyoku)_, the round ended without a winner
+    Ryuukyoku {
+        /// Tenhou's name for an abortive draw, e.g. `yao9` or `reach4`. `None` when
+        /// the wall ran out
+        reason: Option<String>,
+        /// How many points each player gained or lost
+        score_changes: [i32; 4],
+    },
+}
+
+/// The details of a win, as recorded by Tenhou
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Agari {
+    /// Who won
+    pub who: usize,
+    /// Who dealt in, the same as `who` for a tsumo
+    pub from_who: usize,
+    /// The concealed tiles, including the winning tile
+    pub concealed: Vec<Suit>,
+    /// Called melds and closed kans
+    pub melds: Vec<Meld>,
+    /// The tile the hand was won on
+    pub winning_tile: Suit,
+    /// 符 _(fu)_
+    pub fu: u32,
+    /// Points won, before honba and riichi sticks
+    pub points: u32,
+    /// Tenhou's yaku ids, each with its han
+    pub yaku: Vec<(u8, u8)>,
+    /// Tenhou's yaku ids of any yakuman
+    pub yakuman: Vec<u8>,
+    /// ドラ表示牌 _(dora hyoujihai)_
+    pub dora_indicators: Vec<Suit>,
+    /// 裏ドラ表示牌 _(ura dora hyoujihai)_, only shown after riichi
+    pub ura_dora_indicators: Vec<Suit>,
+    /// How many points each player gained or lost, including honba and sticks
+    pub score_changes: [i32; 4],
+}
+
+impl Replay {
+    /// Reads an `.mjlog`. Elements this crate doesn't know about are skipped.
+    /// Can throw a [DecodeErr]
+    ///
+    /// ```
+    /// # use mahjong_encoding::*;
+    /// let log = r#"<mjloggm ver="2.3"><GO type="169"/><UN n0="%41" n1="B" n2="C" n3="D"/>
+    ///     <INIT seed="0,0,0,1,2,12" ten="250,250,250,250" oya="0"
+    ///         hai0="0,4,8" hai1="1,5,9" hai2="2,6,10" hai3="3,7,11"/>
+    ///     <T16/><D16/></mjloggm>"#;
+    /// let replay = Replay::from_mjlog(log).unwrap();
+    ///
+    /// assert_eq!(replay.players[0], "A");
+    /// assert_eq!(
+    ///     replay.rounds[0].events[1],
+    ///     Event::Discard { who: 0, tile: Suit::Characters(RED_FIVE) }
+    /// );
+    /// ```
+    pub fn from_mjlog(input: &str) -> Result<Replay, DecodeErr> {
+        let mut replay = Replay {
+            players: vec![],
+            red_fives: true,
+            rounds: vec![],
+        };
+
+        for element in xml::elements(input)? {
+            let red = replay.red_fives;
+
+            match element.name {
+                "GO" => replay.red_fives = number(&element, "type")? & NO_RED_FIVES == 0,
+                "UN" if replay.players.is_empty() => {
+                    replay.players = ["n0", "n1", "n2", "n3"]
+                        .iter()
+                        .filter_map(|key| element.get(key))
+                        .map(percent_decode)
+                        .collect();
+                }
+                "INIT" => replay.rounds.push(round(&element, red)?),
+                name => {
+                    let Some(round) = replay.rounds.last_mut() else {
+                        continue;
+                    };
+                    if let Some(event) = event(name, &element, red)? {
+                        round.events.push(event);
+                    }
+                }
+            }
+        }
+
+        Ok(replay)
+    }
+}
+
+fn round(element: &Element, red: bool) -> Result<Round, DecodeErr> {
+    let seed = numbers(element, "seed")?;
+    let [kyoku, honba, riichi_sticks, dice1, dice2, dora] = seed[..] else {
+        return Err(DecodeErr::InvalidField { field: "seed" });
+    };
+
+    let scores = element
+        .get("ten")
+        .unwrap_or("")
+        .split(',')
+        .map(|score| score.trim().parse::<i32>().map(|score| score * 100))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| DecodeErr::InvalidField { field: "ten" })?;
+
+    let hand = |key: &'static str| tiles(element, key, red);
+
+    Ok(Round {
+        round_wind: WINDS[(kyoku as usize / 4) % 4],
+        number: (kyoku % 4) as u8 + 1,
+        honba: honba as u8,
+        riichi_sticks: riichi_sticks as u8,
+        dealer: seat(number(element, "oya")?, "oya")?,
+        dice: [dice1 as u8 + 1, dice2 as u8 + 1],
+        dora_indicator: tile(dora, "seed", red)?,
+        scores: scores
+            .try_into()
+            .map_err(|_| DecodeErr::InvalidField { field: "ten" })?,
+        hands: [hand("hai0")?, hand("hai1")?, hand("hai2")?, hand("hai3")?],
+        events: vec![],
+    })
+}
+
+fn event(name: &str, element: &Element, red: bool) -> Result<Option<Event>, DecodeErr> {
+    let who = || seat(number(element, "who")?, "who");
+
+    Ok(Some(match name {
+        "N" => match meld(number(element, "m")?, red)? {
+            Some(meld) => Event::Call { who: who()?, meld },
+            None => Event::Nuki { who: who()? },
+        },
+        "REACH" => match number(element, "step")? {
+            1 => Event::Riichi { who: who()? },
+            _ => Event::RiichiAccepted { who: who()? },
+        },
+        "DORA" => Event::Dora {
+            indicator: tile(number(element, "hai")?, "hai", red)?,
+        },
+        "AGARI" => Event::Win(agari(element, red)?),
+        "RYUUKYOKU" => Event::Ryuukyoku {
+            reason: element.get("type").map(str::to_string),
+            score_changes: score_changes(element)?,
+        },
+        _ => {
+            let mut chars = name.chars();
+            let (Some(letter), Ok(id)) = (chars.next(), chars.as_str().parse()) else {
+                return Ok(None);
+            };
+            let tile = tile(id, "tile", red)?;
+
+            match letter {
+                'T' | 'U' | 'V' | 'W' => Event::Draw {
+                    who: letter as usize - 'T' as usize,
+                    tile,
+                },
+                'D' | 'E' | 'F' | 'G' => Event::Discard {
+                    who: letter as usize - 'D' as usize,
+                    tile,
+                },
+                _ => return Ok(None),
+            }
+        }
+    }))
+}
+
+fn agari(element: &Element, red: bool) -> Result<Agari, DecodeErr> {
+    let ten = numbers(element, "ten")?;
+    let yaku = optional_numbers(element, "yaku")?;
+
+    Ok(Agari {
+        who: seat(number(element, "who")?, "who")?,
+        from_who: seat(number(element, "fromWho")?, "fromWho")?,
+        concealed: tiles(element, "hai", red)?,
+        melds: optional_numbers(element, "m")?
+            .into_iter()
+            .filter_map(|m| meld(m, red).transpose())
+            .collect::<Result<_, _>>()?,
+        winning_tile: tile(number(element, "machi")?, "machi", red)?,
+        fu: *ten
+            .first()
+            .ok_or(DecodeErr::InvalidField { field: "ten" })?,
+        points: *ten.get(1).ok_or(DecodeErr::InvalidField { field: "ten" })?,
+        yaku: yaku
+            .chunks_exact(2)
+            .map(|pair| (pair[0] as u8, pair[1] as u8))
+            .collect(),
+        yakuman: optional_numbers(element, "yakuman")?
+            .into_iter()
+            .map(|id| id as u8)
+            .collect(),
+        dora_indicators: optional_tiles(element, "doraHai", red)?,
+        ura_dora_indicators: optional_tiles(element, "doraHaiUra", red)?,
+        score_changes: score_changes(element)?,
+    })
+}
+
+/// Decodes the `m` attribute of a call, `None` for a 北抜き _(kita nuki)_
+fn meld(m: u32, red: bool) -> Result<Option<Meld>, DecodeErr> {
+    let invalid = DecodeErr::InvalidField { field: "m" };
+    let from = match m & 3 {
+        1 => Some(Seat::Right),
+        2 => Some(Seat::Across),
+        3 => Some(Seat::Left),
+        _ => None,
+    };
+    let tile = |id: u32| tile(id, "m", red);
+
+    if m & 0x4 != 0 {
+        let t = m >> 10;
+        let (t, called) = (t / 3, t % 3);
+        let first = (t / 7) * 9 + t % 7;
+
+        let ids = (0..3)
+            .map(|i| (first + i) * 4 + (m >> (3 + 2 * i) & 3))
+            .collect::<Vec<_>>();
+        let tiles = ids
+            .iter()
+            .map(|id| tile(*id))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let from = from.ok_or(invalid)?;
+        return Meld::called(MeldKind::Chi, tiles.clone(), tiles[called as usize], from).map(Some);
+    }
+
+    if m & 0x18 != 0 {
+        let unused = m >> 5 & 3;
+        let t = m >> 9;
+        let (first, called) = (t / 3 * 4, t % 3);
+
+        let ids = (0..4).filter(|i| *i != unused).map(|i| first + i);
+        let tiles = ids.map(tile).collect::<Result<Vec<_>, _>>()?;
+
+        let from = from.ok_or(invalid)?;
+        let pon = Meld::called(MeldKind::Pon, tiles.clone(), tiles[called as usize], from)?;
+        return match m & 0x10 {
+            0 => Ok(Some(pon)),
+            _ => pon.upgrade(tile(first + unused)?).map(Some),
+        };
+    }
+
+    if m & 0x20 != 0 {
+        return Ok(None);
+    }
+
+    let called = m >> 8;
+    let first = called / 4 * 4;
+    let tiles = (first..first + 4)
+        .map(tile)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    match from {
+        Some(from) => Meld::called(MeldKind::OpenKan, tiles, tile(called)?, from),
+        None => Meld::new(MeldKind::ClosedKan, tiles),
+    }
+    .map(Some)
+}
+
+fn tile(id: u32, field: &'static str, red: bool) -> Result<Suit, DecodeErr> {
+    let tile = u8::try_from(id)
+        .ok()
+        .and_then(Suit::from_tenhou_id)
+        .ok_or(DecodeErr::InvalidField { field })?;
+
+    Ok(if red { tile } else { tile.normalize() })
+}
+
+fn tiles(element: &Element, field: &'static str, red: bool) -> Result<Vec<Suit>, DecodeErr> {
+    optional_numbers(element, field)?
+        .into_iter()
+        .map(|id| tile(id, field, red))
+        .collect()
+}
+
+fn optional_tiles(
+    element: &Element,
+    field: &'static str,
+    red: bool,
+) -> Result<Vec<Suit>, DecodeErr> {
+    match element.get(field) {
+        Some(_) => tiles(element, field, red),
+        None => Ok(vec![]),
+    }
+}
+
+fn seat(seat: u32, field: &'static str) -> Result<usize, DecodeErr> {
+    match seat {
+        0..=3 => Ok(seat as usize),
+        _ => Err(DecodeErr::InvalidField { field }),
+    }
+}
+
+fn score_changes(element: &Element) -> Result<[i32; 4], DecodeErr> {
+    let invalid = DecodeErr::InvalidField { field: "sc" };
+    let sc = element.get("sc").ok_or(invalid)?;
+
+    let values = sc
+        .split(',')
+        .map(|value| value.trim().parse::<i32>().map_err(|_| invalid))
+        .collect::<Result<Vec<_>, _>>()?;
+    if values.len() != 8 {
+        return Err(invalid);
+    }
+
+    Ok([0, 1, 2, 3].map(|seat| values[seat * 2 + 1] * 100))
+}
+
+fn number(element: &Element, field: &'static str) -> Result<u32, DecodeErr> {
+    element
+        .get(field)
+        .and_then(|value| value.trim().parse().ok())
+        .ok_or(DecodeErr::InvalidField { field })
+}
+
+fn numbers(element: &Element, field: &'static str) -> Result<Vec<u32>, DecodeErr> {
+    element
+        .get(field)
+        .ok_or(DecodeErr::InvalidField { field })?;
+    optional_numbers(element, field)
+}
+
+/// A comma separated list of numbers, empty if the attribute is missing or blank
+fn optional_numbers(element: &Element, field: &'static str) -> Result<Vec<u32>, DecodeErr> {
+    let value = element.get(field).unwrap_or("").trim();
+    if value.is_empty() {
+        return Ok(vec![]);
+    }
+
+    value
+        .split(',')
+        .map(|number| {
+            number
+                .trim()
+                .parse()
+                .map_err(|_| DecodeErr::InvalidField { field })
+        })
+        .collect()
+}
+
+/// Names are stored as `%XX` escaped UTF-8
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut output = vec![];
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let hex = value
+            .get(i + 1..i + 3)
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                output.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                output.push(byte);
+                i += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&output).into_owned()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const LOG: &str = r#"<mjloggm ver="2.3">
+<SHUFFLE seed="mt19937ar-sha512-n288-base64,abc" ref=""/>
+<GO type="169" lobby="0"/>
+<UN n0="%E3%81%82" n1="B" n2="C" n3="D" dan="0,0,0,0"/>
+<TAIKYOKU oya="0"/>
+<INIT seed="5,1,2,3,4,12" ten="240,250,-10,250" oya="1"
+    hai0="0,4,8,12,16,20,24,28,32,36,40,44,48"
+    hai1="1,5,9,13,17,21,25,29,33,37,41,45,49"
+    hai2="2,6,10,14,18,22,26,30,34,38,42,46,50"
+    hai3="3,7,11,15,19,23,27,31,35,39,43,47,51"/>
+<U52/><E88/><N who="0" m="7"/><D133/>
+<N who="2" m="51306"/><N who="3" m="28751"/>
+<REACH who="1" step="1"/><U108/><E109/><REACH who="1" ten="240,240,260,250" step="2"/>
+<N who="1" m="27648"/><DORA hai="120"/>
+<AGARI ba="1,1" hai="0,4,8,52,53,54" m="27648" machi="54" ten="40,5200,0" yaku="1,1,0,1" doraHai="12,120" doraHaiUra="20" who="1" fromWho="2" sc="240,63,250,0,260,-53,250,0"/>
+<INIT seed="6,0,0,0,0,3" ten="300,250,210,240" oya="2" hai0="" hai1="" hai2="" hai3=""/>
+<RYUUKYOKU ba="0,0" sc="300,0,250,0,210,0,240,0" type="yao9"/>
+</mjloggm>"#;
+
+    fn tiles(mpsz: &str) -> Vec<Suit> {
+        Suit::from_mpsz(mpsz).unwrap()
+    }
+
+    #[test]
+    fn maps_every_tenhou_id() {
+        for id in 0..136u8 {
+            let tile = Suit::from_tenhou_id(id).unwrap();
+
+            assert_eq!(tile.index(), Some(id as usize / 4));
+            assert_eq!(tile.is_red(), matches!(id, 16 | 52 | 88));
+        }
+    }
+
+    #[test]
+    fn reads_rounds() {
+        let replay = Replay::from_mjlog(LOG).unwrap();
+        let round = &replay.rounds[0];
+
+        assert_eq!(replay.players, ["あ", "B", "C", "D"]);
+        assert!(replay.red_fives);
+        assert_eq!(replay.rounds.len(), 2);
+        assert_eq!((round.round_wind, round.number), (Wind::South, 2));
+        assert_eq!((round.honba, round.riichi_sticks, round.dealer), (1, 2, 1));
+        assert_eq!(round.dice, [4, 5]);
+        assert_eq!(round.dora_indicator, Suit::Characters(4));
+        assert_eq!(round.scores, [24000, 25000, -1000, 25000]);
+        assert_eq!(Suit::to_mpsz(&round.hands[0]), "123406789m1234p");
+        assert_eq!(round.hands[3][4], Suit::Characters(5));
+    }
+
+    #[test]
+    fn reads_draws_discards_and_riichi() {
+        let replay = Replay::from_mjlog(LOG).unwrap();
+        let events = &replay.rounds[0].events;
+
+        assert_eq!(
+            events[0],
+            Event::Draw {
+                who: 1,
+                tile: Suit::Dots(RED_FIVE)
+            }
+        );
+        assert_eq!(
+            events[1],
+            Event::Discard {
+                who: 1,
+                tile: Suit::Bamboo(RED_FIVE)
+            }
+        );
+        assert_eq!(events[6], Event::Riichi { who: 1 });
+        assert_eq!(events[9], Event::RiichiAccepted { who: 1 });
+        assert_eq!(
+            events[11],
+            Event::Dora {
+                indicator: Suit::Wind(Wind::North)
+            }
+        );
+    }
+
+    #[test]
+    fn reads_calls() {
+        let replay = Replay::from_mjlog(LOG).unwrap();
+        let events = &replay.rounds[0].events;
+        let meld = |index: usize| match &events[index] {
+            Event::Call { meld, .. } => meld.clone(),
+            event => panic!("{event:?} isn't a call"),
+        };
+
+        let chi = meld(2);
+        assert_eq!(chi.kind(), MeldKind::Chi);
+        assert_eq!(chi.tiles(), tiles("123m"));
+        assert_eq!(
+            chi.claim(),
+            Some(Claim {
+                tile: Suit::Characters(1),
+                from: Seat::Left
+            })
+        );
+
+        let pon = meld(4);
+        assert_eq!(pon.kind(), MeldKind::Pon);
+        assert_eq!(pon.claim().unwrap().from, Seat::Across);
+
+        let red_chi = meld(5);
+        assert_eq!(red_chi.tiles(), tiles("340p"));
+        assert_eq!(red_chi.claim().unwrap().tile, Suit::Dots(4));
+
+        let kan = meld(10);
+        assert_eq!(kan.kind(), MeldKind::ClosedKan);
+        assert_eq!(kan.tiles(), tiles("1111z"));
+    }
+
+    #[test]
+    fn reads_results() {
+        let replay = Replay::from_mjlog(LOG).unwrap();
+
+        let Event::Win(agari) = &replay.rounds[0].events[12] else {
+            panic!("expected a win");
+        };
+        assert_eq!((agari.who, agari.from_who), (1, 2));
+        assert_eq!(agari.concealed, tiles("123m055p"));
+        assert_eq!(agari.melds.len(), 1);
+        assert_eq!(agari.winning_tile, Suit::Dots(5));
+        assert_eq!((agari.fu, agari.points), (40, 5200));
+        assert_eq!(agari.yaku, [(1, 1), (0, 1)]);
+        assert_eq!(agari.dora_indicators, tiles("4m4z"));
+        assert_eq!(agari.ura_dora_indicators, tiles("6m"));
+        assert_eq!(agari.score_changes, [6300, 0, -5300, 0]);
+
+        assert_eq!(
+            replay.rounds[1].events,
+            [Event::Ryuukyoku {
+                reason: Some("yao9".to_string()),
+                score_changes: [0; 4],
+            }]
+        );
+    }
+
+    #[test]
+    fn drops_red_fives_when_the_game_had_none() {
+        let log = LOG.replace(r#"type="169""#, r#"type="171""#);
+        let replay = Replay::from_mjlog(&log).unwrap();
+
+        assert!(!replay.red_fives);
+        assert_eq!(Suit::count_red(&replay.rounds[0].hands[0]), 0);
+    }
+
+    #[test]
+    fn rejects_bad_ids() {
+        let log = LOG.replace("<U52/>", "<U136/>");
+        assert_eq!(
+            Replay::from_mjlog(&log),
+            Err(DecodeErr::InvalidField { field: "tile" })
+        );
+    }
+}
@@ -0,0 +1,219 @@
+//! MPSZ notation, as used by Tenhou and most mahjong forums, e.g. `123m456p789s11z`.
+//!
+//! Digits are grouped in front of the suit letter they belong to, `0` marks a red
+//! five and honours are written `1z`–`7z` (東南西北白發中).
+
+use crate::*;
+
+const SEPARATORS: &[u8] = b" \t\r\n,;|-/";
+const EXPECTED: &str = "a digit, one of m, p, s or z, or a separator";
+
+impl Suit {
+    /// Converts an array or vec of [Suit] into MPSZ notation. Runs of tiles in the
+    /// same suit share a single suit letter, the order of the hand is kept as is.
+    ///
+    /// ```
+    /// # use mahjong_encoding::*;
+    /// let hand = [Suit::Characters(1), Suit::Characters(2), Suit::Dots(RED_FIVE)];
+    /// assert_eq!(Suit::to_mpsz(&hand), "12m0p");
+    /// ```
+    pub fn to_mpsz(hand: &[Suit]) -> String {
+        let mut output = String::new();
+        let mut current = None;
+
+        for tile in hand {
+            let (rank, suit) = tile.to_mpsz_parts();
+
+            if current.is_some_and(|current| current != suit) {
+                output.extend(current);
+            }
+
+            output.push(rank);
+            current = Some(suit);
+        }
+
+        output.extend(current);
+        output
+    }
+
+    /// Converts from MPSZ notation into a hand. Whitespace and the usual separators
+    /// (`,`, `;`, `|`, `-`, `/`) are allowed between groups. Can throw a [DecodeErr]
+    /// pointing at the offending position in the input.
+    ///
+    /// ```
+    /// # use mahjong_encoding::*;
+    /// let hand = Suit::from_mpsz("123m 406p 11z").ok().unwrap();
+    /// assert_eq!(hand[4], Suit::Dots(RED_FIVE));
+    /// assert_eq!(hand[6], Suit::Wind(Wind::East));
+    /// ```
+    pub fn from_mpsz(input: &str) -> Result<Vec<Suit>, DecodeErr> {
+        let mut hand = vec![];
+        let mut pending: Vec<(usize, u8)> = vec![];
+
+        for (position, &byte) in input.as_bytes().iter().enumerate() {
+            match byte {
+                b'0'..=b'9' => pending.push((position, byte - b'0')),
+                b'm' | b'p' | b's' | b'z' => {
+                    if pending.is_empty() {
+                        return Err(DecodeErr::MissingRank { position });
+                    }
+
+                    for (position, rank) in pending.drain(..) {
+                        hand.push(Suit::from_mpsz_parts(rank, byte, position)?);
+                    }
+                }
+                _ if SEPARATORS.contains(&byte) => {
+                    if let Some(&(position, _)) = pending.first() {
+                        return Err(DecodeErr::MissingSuit { position });
+                    }
+                }
+                _ => {
+                    return Err(DecodeErr::InvalidCharacter {
+                        byte,
+                        position,
+                        expected: EXPECTED,
+                    })
+                }
+            }
+        }
+
+        match pending.first() {
+            Some(&(position, _)) => Err(DecodeErr::MissingSuit { position }),
+            None => Ok(hand),
+        }
+    }
+
+    fn to_mpsz_parts(self) -> (char, char) {
+        let rank = |n: u8| match n {
+            RED_FIVE => '0',
+            n => char::from_digit(n as u32, 10)
+                .filter(|_| n != 0)
+                .unwrap_or('?'),
+        };
+
+        match self {
+            Suit::Characters(n) => (rank(n), 'm'),
+            Suit::Dots(n) => (rank(n), 'p'),
+            Suit::Bamboo(n) => (rank(n), 's'),
+            Suit::Wind(wind) => (
+                match wind {
+                    Wind::East => '1',
+                    Wind::South => '2',
+                    Wind::West => '3',
+                    Wind::North => '4',
+                },
+                'z',
+            ),
+            Suit::Dragon(dragon) => (
+                match dragon {
+                    Dragon::White => '5',
+                    Dragon::Green => '6',
+                    Dragon::Red => '7',
+                },
+                'z',
+            ),
+        }
+    }
+
+    fn from_mpsz_parts(rank: u8, suit: u8, position: usize) -> Result<Suit, DecodeErr> {
+        let rank = if rank == 0 { RED_FIVE } else { rank };
+
+        Ok(match suit {
+            b'm' => Suit::Characters(rank),
+            b'p' => Suit::Dots(rank),
+            b's' => Suit::Bamboo(rank),
+            _ => match rank {
+                1 => Suit::Wind(Wind::East),
+                2 => Suit::Wind(Wind::South),
+                3 => Suit::Wind(Wind::West),
+                4 => Suit::Wind(Wind::North),
+                5 => Suit::Dragon(Dragon::White),
+                6 => Suit::Dragon(Dragon::Green),
+                7 => Suit::Dragon(Dragon::Red),
+                _ => return Err(DecodeErr::InvalidHonour { position }),
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parses_grouped_digits() {
+        let hand = Suit::from_mpsz("123m456p789s11z").ok().unwrap();
+
+        assert_eq!(hand.len(), 11);
+        assert_eq!(hand[0], Suit::Characters(1));
+        assert_eq!(hand[3], Suit::Dots(4));
+        assert_eq!(hand[8], Suit::Bamboo(9));
+        assert_eq!(hand[10], Suit::Wind(Wind::East));
+    }
+
+    #[test]
+    fn parses_honours_and_red_fives() {
+        let hand = Suit::from_mpsz("0m0p0s1234567z").ok().unwrap();
+
+        assert_eq!(
+            hand,
+            [
+                Suit::Characters(RED_FIVE),
+                Suit::Dots(RED_FIVE),
+                Suit::Bamboo(RED_FIVE),
+                Suit::Wind(Wind::East),
+                Suit::Wind(Wind::South),
+                Suit::Wind(Wind::West),
+                Suit::Wind(Wind::North),
+                Suit::Dragon(Dragon::White),
+                Suit::Dragon(Dragon::Green),
+                Suit::Dragon(Dragon::Red),
+            ]
+        );
+    }
+
+    #[test]
+    fn ignores_separators_between_groups() {
+        let a = Suit::from_mpsz("123m456p").ok().unwrap();
+        let b = Suit::from_mpsz(" 123m, 456p |\n").ok().unwrap();
+
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn round_trips_through_mpsz() {
+        let input = "234567m45677p456s";
+        let hand = Suit::from_mpsz(input).ok().unwrap();
+
+        assert_eq!(Suit::to_mpsz(&hand), input);
+        assert_eq!(Suit::to_string(&hand), "yz0123UVWXXklm");
+    }
+
+    #[test]
+    fn reports_error_positions() {
+        assert!(matches!(
+            Suit::from_mpsz("123m45"),
+            Err(DecodeErr::MissingSuit { position: 4 })
+        ));
+        assert!(matches!(
+            Suit::from_mpsz("12 3m"),
+            Err(DecodeErr::MissingSuit { position: 0 })
+        ));
+        assert!(matches!(
+            Suit::from_mpsz("123mp"),
+            Err(DecodeErr::MissingRank { position: 4 })
+        ));
+        assert!(matches!(
+            Suit::from_mpsz("11z89z"),
+            Err(DecodeErr::InvalidHonour { position: 3 })
+        ));
+        assert!(matches!(
+            Suit::from_mpsz("123x"),
+            Err(DecodeErr::InvalidCharacter {
+                byte: b'x',
+                position: 3,
+                ..
+            })
+        ));
+    }
+}
@@ -0,0 +1,417 @@
+use crate::yaku::Block;
+use crate::*;
+
+/// Why a hand was awarded some 符 _(fu)_
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum FuReason {
+    /// 副底 _(fuutei)_, every hand starts with 20 fu
+    Base,
+    /// 七対子 _(chiitoitsu)_ are always worth 25 fu
+    SevenPairs,
+    /// 門前加符 _(menzen kafu)_, won by ron with a closed hand
+    ClosedRon,
+    /// ツモ符 _(tsumo fu)_, won on a self drawn tile
+    Tsumo,
+    /// The wait was a single tile, see [Wait]
+    Wait(Wait),
+    /// The pair is a dragon, the seat wind or the round wind
+    Pair(Suit),
+    /// A triplet or quad
+    Group {
+        /// The group itself
+        group: Group,
+        /// Whether it was neither called nor completed by ron
+        concealed: bool,
+        /// Whether it is a quad
+        kan: bool,
+    },
+    /// An open hand with no other fu is worth 30
+    OpenPinfu,
+}
+
+/// A line of the fu breakdown
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Fu {
+    /// What the fu are for
+    pub reason: FuReason,
+    /// How many fu
+    pub fu: u8,
+}
+
+/// 満貫 _(mangan)_ and above, where fu no longer matter
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Limit {
+    /// 満貫 _(mangan)_, 5 han or more than 2000 base points
+    Mangan,
+    /// 跳満 _(haneman)_, 6–7 han
+    Haneman,
+    /// 倍満 _(baiman)_, 8–10 han
+    Baiman,
+    /// 三倍満 _(sanbaiman)_, 11–12 han
+    Sanbaiman,
+    /// 数え役満 _(kazoe yakuman)_, 13 han or more
+    KazoeYakuman,
+    /// 役満 _(yakuman)_, times however many were scored
+    Yakuman(u8),
+}
+
+/// Who pays the winner, before sticks on the table
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Payment {
+    /// The discarder pays everything
+    Ron(u32),
+    /// The dealer won by tsumo, everyone pays the same
+    DealerTsumo(u32),
+    /// A non dealer won by tsumo
+    Tsumo {
+        /// Paid by the dealer
+        dealer: u32,
+        /// Paid by each of the other two players
+        others: u32,
+    },
+}
+
+impl Payment {
+    /// Total paid to the winner
+    pub fn total(&self) -> u32 {
+        match self {
+            Payment::Ron(points) => *points,
+            Payment::DealerTsumo(points) => points * 3,
+            Payment::Tsumo { dealer, others } => dealer + others * 2,
+        }
+    }
+}
+
+/// The full breakdown of how a winning hand was scored, see [Win::score]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Score {
+    /// The yaku of the reading that scored best
+    pub evaluation: Evaluation,
+    /// Every line that went into the fu, before rounding
+    pub fu_breakdown: Vec<Fu>,
+    /// Fu, rounded up to the next 10 except for 七対子 _(chiitoitsu)_
+    pub fu: u8,
+    /// Han from yaku alone
+    pub yaku_han: u8,
+    /// ドラ _(dora)_
+    pub dora: u8,
+    /// 裏ドラ _(ura dora)_, only counted after riichi
+    pub ura_dora: u8,
+    /// 赤ドラ _(aka dora)_, from [RED_FIVE] tiles
+    pub aka_dora: u8,
+    /// Total han, including dora
+    pub han: u8,
+    /// The limit reached, if any
+    pub limit: Option<Limit>,
+    /// 基本点 _(kihonten)_, before multiplying for each payer
+    pub base_points: u32,
+    /// What each player pays, including 本場 _(honba)_
+    pub payment: Payment,
+    /// Points from riichi sticks on the table
+    pub riichi_sticks: u32,
+}
+
+impl Score {
+    /// Everything the winner gets, payments and riichi sticks
+    pub fn total(&self) -> u32 {
+        self.payment.total() + self.riichi_sticks
+    }
+}
+
+impl Win {
+    /// Scores the hand, picking whichever reading is worth the most points.
+    /// `None` if the hand isn't complete or has no yaku. Can throw a [DecodeErr]
+    ///
+    /// ```
+    /// # use mahjong_encoding::*;
+    /// let hand = Suit::from_mpsz("223344m456p678s55p").unwrap();
+    /// let win = Win::new(hand, vec![], Suit::Characters(2), WinType::Ron, Wind::South, Wind::East);
+    /// let score = win.score().unwrap().unwrap();
+    ///
+    /// assert_eq!((score.han, score.fu), (3, 30));
+    /// assert_eq!(score.payment, Payment::Ron(3900));
+    /// ```
+    pub fn score(&self) -> Result<Option<Score>, DecodeErr> {
+        Ok(self
+            .evaluate()?
+            .into_iter()
+            .filter(|evaluation| !evaluation.yaku.is_empty())
+            .map(|evaluation| self.score_of(evaluation))
+            .max_by_key(|score| (score.total(), score.han, score.fu)))
+    }
+
+    fn score_of(&self, evaluation: Evaluation) -> Score {
+        let fu_breakdown = self.fu(&evaluation);
+        let fu = match evaluation.decomposition.shape {
+            Shape::SevenPairs(_) => 25,
+            _ => {
+                let fu = fu_breakdown.iter().map(|fu| fu.fu as u32).sum::<u32>();
+                fu.div_ceil(10) as u8 * 10
+            }
+        };
+
+        let yaku_han = evaluation.han();
+        let dora = self.dora;
+        let ura_dora = if self.riichi == Riichi::None {
+            0
+        } else {
+            self.ura_dora
+        };
+        let aka_dora = Suit::count_red(&self.all_tiles()) as u8;
+        let han = yaku_han
+            .saturating_add(dora)
+            .saturating_add(ura_dora)
+            .saturating_add(aka_dora);
+
+        let limit = match (evaluation.yakuman(), han) {
+            (yakuman @ 1.., _) => Some(Limit::Yakuman(yakuman)),
+            (_, 13..) => Some(Limit::KazoeYakuman),
+            (_, 11..=12) => Some(Limit::Sanbaiman),
+            (_, 8..=10) => Some(Limit::Baiman),
+            (_, 6..=7) => Some(Limit::Haneman),
+            (_, 5) => Some(Limit::Mangan),
+            _ if fu as u32 * 2u32.pow(han as u32 + 2) >= 2000 => Some(Limit::Mangan),
+            _ => None,
+        };
+
+        let base_points = match limit {
+            Some(Limit::Yakuman(yakuman)) => 8000 * yakuman as u32,
+            Some(Limit::KazoeYakuman) => 8000,
+            Some(Limit::Sanbaiman) => 6000,
+            Some(Limit::Baiman) => 4000,
+            Some(Limit::Haneman) => 3000,
+            Some(Limit::Mangan) => 2000,
+            None => fu as u32 * 2u32.pow(han as u32 + 2),
+        };
+
+        let honba = self.honba as u32;
+        let round = |points: u32| points.div_ceil(100) * 100;
+        let payment = match (self.win_type, self.is_dealer()) {
+            (WinType::Ron, true) => Payment::Ron(round(base_points * 6) + honba * 300),
+            (WinType::Ron, false) => Payment::Ron(round(base_points * 4) + honba * 300),
+            (WinType::Tsumo, true) => Payment::DealerTsumo(round(base_points * 2) + honba * 100),
+            (WinType::Tsumo, false) => Payment::Tsumo {
+                dealer: round(base_points * 2) + honba * 100,
+                others: round(base_points) + honba * 100,
+            },
+        };
+
+        Score {
+            evaluation,
+            fu_breakdown,
+            fu,
+            yaku_han,
+            dora,
+            ura_dora,
+            aka_dora,
+            han,
+            limit,
+            base_points,
+            payment,
+            riichi_sticks: self.riichi_sticks as u32 * 1000,
+        }
+    }
+
+    fn fu(&self, evaluation: &Evaluation) -> Vec<Fu> {
+        let line = |reason: FuReason, fu: u8| Fu { reason, fu };
+        let decomposition = &evaluation.decomposition;
+        let tsumo = self.win_type == WinType::Tsumo;
+
+        let (pair, groups) = match &decomposition.shape {
+            Shape::SevenPairs(_) => return vec![line(FuReason::SevenPairs, 25)],
+            Shape::ThirteenOrphans { .. } => return vec![line(FuReason::Base, 20)],
+            Shape::Standard { pair, groups } => (*pair, groups),
+        };
+
+        let mut breakdown = vec![line(FuReason::Base, 20)];
+
+        if evaluation.yaku.contains(&Yaku::Pinfu) {
+            if !tsumo {
+                breakdown.push(line(FuReason::ClosedRon, 10));
+            }
+            return breakdown;
+        }
+
+        if !tsumo && self.is_closed() {
+            breakdown.push(line(FuReason::ClosedRon, 10));
+        }
+        if tsumo {
+            breakdown.push(line(FuReason::Tsumo, 2));
+        }
+        if matches!(
+            decomposition.wait,
+            Wait::Kanchan | Wait::Penchan | Wait::Tanki
+        ) {
+            breakdown.push(line(FuReason::Wait(decomposition.wait), 2));
+        }
+
+        let pair_fu = match pair {
+            Suit::Dragon(_) => 2,
+            Suit::Wind(wind) => {
+                2 * (wind == self.seat_wind) as u8 + 2 * (wind == self.round_wind) as u8
+            }
+            _ => 0,
+        };
+        if pair_fu > 0 {
+            breakdown.push(line(FuReason::Pair(pair), pair_fu));
+        }
+
+        for Block {
+            group,
+            concealed,
+            kan,
+        } in self.blocks(decomposition, groups)
+        {
+            let Group::Triplet(tile) = group else {
+                continue;
+            };
+
+            let mut fu = 2;
+            if !tile.is_simple() {
+                fu *= 2;
+            }
+            if concealed {
+                fu *= 2;
+            }
+            if kan {
+                fu *= 4;
+            }
+            breakdown.push(line(
+                FuReason::Group {
+                    group,
+                    concealed,
+                    kan,
+                },
+                fu,
+            ));
+        }
+
+        if breakdown.len() == 1 && !self.is_closed() {
+            breakdown.push(line(FuReason::OpenPinfu, 10));
+        }
+
+        breakdown
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn win(hand: &str, winning_tile: &str, win_type: WinType, seat_wind: Wind) -> Win {
+        Win::new(
+            Suit::from_mpsz(hand).unwrap(),
+            vec![],
+            Suit::from_mpsz(winning_tile).unwrap()[0],
+            win_type,
+            seat_wind,
+            Wind::East,
+        )
+    }
+
+    fn score(win: &Win) -> Score {
+        win.score().unwrap().unwrap()
+    }
+
+    #[test]
+    fn scores_pinfu_ron_and_tsumo() {
+        let ron = score(&win("223344m456p678s55p", "2m", WinType::Ron, Wind::South));
+        assert_eq!((ron.han, ron.fu), (3, 30));
+        assert_eq!(ron.payment, Payment::Ron(3900));
+
+        let tsumo = score(&win("223344m456p678s55p", "2m", WinType::Tsumo, Wind::East));
+        assert_eq!((tsumo.han, tsumo.fu), (4, 20));
+        assert_eq!(tsumo.payment, Payment::DealerTsumo(2600));
+    }
+
+    #[test]
+    fn breaks_down_fu() {
+        let mut win = win("111m222p333s44z567p", "3s", WinType::Ron, Wind::South);
+        assert_eq!(win.score(), Ok(None));
+
+        win.riichi = Riichi::Riichi;
+        let score = score(&win);
+        let fu = score
+            .fu_breakdown
+            .iter()
+            .map(|fu| fu.fu)
+            .collect::<Vec<_>>();
+
+        assert_eq!(fu, [20, 10, 8, 4, 2]);
+        assert_eq!(score.fu, 50);
+        assert_eq!(score.payment, Payment::Ron(1600));
+    }
+
+    #[test]
+    fn seven_pairs_are_worth_25_fu() {
+        let mut win = win("1122m3344p5566s77z", "7z", WinType::Ron, Wind::South);
+        win.riichi = Riichi::Riichi;
+        let score = score(&win);
+
+        assert_eq!((score.han, score.fu), (3, 25));
+        assert_eq!(score.payment, Payment::Ron(3200));
+    }
+
+    #[test]
+    fn open_hands_without_fu_are_worth_30() {
+        let mut win = win("234m456p678s55p", "8s", WinType::Ron, Wind::South);
+        win.melds = vec![Meld::new(MeldKind::Chi, Suit::from_mpsz("234s").unwrap()).unwrap()];
+        let score = score(&win);
+
+        assert_eq!(score.fu, 30);
+        assert_eq!(score.payment, Payment::Ron(1000));
+    }
+
+    #[test]
+    fn counts_dora_and_red_fives() {
+        let mut win = win("223344m406p678s55p", "2m", WinType::Ron, Wind::South);
+        win.dora = 2;
+        win.ura_dora = 3;
+        let score = score(&win);
+
+        assert_eq!((score.yaku_han, score.dora, score.ura_dora), (3, 2, 0));
+        assert_eq!(score.aka_dora, 1);
+        assert_eq!(score.limit, Some(Limit::Haneman));
+        assert_eq!(score.payment, Payment::Ron(12000));
+    }
+
+    #[test]
+    fn pays_limit_hands_honba_and_sticks() {
+        let mut win = win("555666777z11m234s", "2s", WinType::Tsumo, Wind::West);
+        win.honba = 2;
+        win.riichi_sticks = 1;
+        let score = score(&win);
+
+        assert_eq!(score.limit, Some(Limit::Yakuman(1)));
+        assert_eq!(
+            score.payment,
+            Payment::Tsumo {
+                dealer: 16200,
+                others: 8200,
+            }
+        );
+        assert_eq!(score.total(), 16200 + 2 * 8200 + 1000);
+    }
+
+    #[test]
+    fn rounds_up_to_mangan() {
+        let mut win = win("111m222p333s44z567p", "5p", WinType::Ron, Wind::East);
+        win.riichi = Riichi::Riichi;
+        win.dora = 1;
+        let score = score(&win);
+
+        assert_eq!((score.han, score.fu), (4, 50));
+        assert_eq!(score.limit, Some(Limit::Mangan));
+        assert_eq!(score.payment, Payment::Ron(12000));
+    }
+
+    #[test]
+    fn caps_han_instead_of_overflowing() {
+        let mut win = win("111m222p333s44z567p", "5p", WinType::Ron, Wind::East);
+        win.dora = u8::MAX;
+        let score = score(&win);
+
+        assert_eq!(score.han, u8::MAX);
+        assert_eq!(score.limit, Some(Limit::KazoeYakuman));
+    }
+}
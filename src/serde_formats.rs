@@ -0,0 +1,229 @@
+//! Alternative serde representations, for use with `#[serde(with = "...")]`.
+//!
+//! With the `serde` feature [Suit](crate::Suit), [Wind](crate::Wind) and [Dragon](crate::Dragon) serialize as plain enums,
+//! e.g. `{"Dots":5}`, and tiles that aren't [valid](crate::Suit::is_valid) are rejected
+//! when read. The modules here trade that for something shorter:
+//!
+//! - [text] writes a whole hand as a single string, see [Suit::to_string](crate::Suit::to_string)
+//! - [mpsz] writes a hand as a list of per tile strings, e.g. `["1m","0p","7z"]`
+//! - [tile] writes a single tile the same way, e.g. `"0p"`
+//!
+//! ```
+//! # use mahjong_encoding::*;
+//! #[derive(serde::Serialize, serde::Deserialize)]
+//! struct Stored {
+//!     #[serde(with = "mahjong_encoding::serde_formats::text")]
+//!     hand: Vec<Suit>,
+//!     #[serde(with = "mahjong_encoding::serde_formats::mpsz")]
+//!     pond: Vec<Suit>,
+//!     #[serde(with = "mahjong_encoding::serde_formats::tile")]
+//!     winning_tile: Suit,
+//! }
+//!
+//! let stored = Stored {
+//!     hand: Suit::from_mpsz("123m").unwrap(),
+//!     pond: Suit::from_mpsz("19p").unwrap(),
+//!     winning_tile: Suit::Dots(RED_FIVE),
+//! };
+//!
+//! assert_eq!(
+//!     serde_json::to_string(&stored).unwrap(),
+//!     r#"{"hand":"xyz","pond":["1p","9p"],"winning_tile":"0p"}"#
+//! );
+//! ```
+
+/// The derived form of [Suit](crate::Suit), only accepted once it is checked to be a
+/// valid tile
+#[derive(serde::Deserialize)]
+#[serde(rename = "Suit")]
+pub(crate) enum Structured {
+    Dots(u8),
+    Bamboo(u8),
+    Characters(u8),
+    Wind(crate::Wind),
+    Dragon(crate::Dragon),
+}
+
+impl TryFrom<Structured> for crate::Suit {
+    type Error = crate::DecodeErr;
+
+    fn try_from(structured: Structured) -> Result<crate::Suit, crate::DecodeErr> {
+        use crate::Suit;
+
+        let tile = match structured {
+            Structured::Dots(n) => Suit::Dots(n),
+            Structured::Bamboo(n) => Suit::Bamboo(n),
+            Structured::Characters(n) => Suit::Characters(n),
+            Structured::Wind(wind) => Suit::Wind(wind),
+            Structured::Dragon(dragon) => Suit::Dragon(dragon),
+        };
+
+        match tile.is_valid() {
+            true => Ok(tile),
+            false => Err(crate::DecodeErr::InvalidTile { tile }),
+        }
+    }
+}
+
+/// A whole hand as a single string, as written by [Suit::to_string](crate::Suit::to_string)
+pub mod text {
+    use ::serde::de::Error;
+    use ::serde::{Deserialize, Deserializer, Serializer};
+
+    use crate::*;
+
+    /// Writes the hand with [Suit::to_string]
+    pub fn serialize<S: Serializer>(hand: &[Suit], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&Suit::to_string(hand))
+    }
+
+    /// Reads the hand with [Suit::from_string]
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Suit>, D::Error> {
+        let text = <std::borrow::Cow<str>>::deserialize(deserializer)?;
+        Suit::from_string(&text).map_err(Error::custom)
+    }
+}
+
+/// A hand as a list of tiles, each written in MPSZ notation
+pub mod mpsz {
+    use ::serde::ser::SerializeSeq;
+    use ::serde::{Deserialize, Deserializer, Serializer};
+
+    use crate::*;
+
+    /// Writes each tile like [super::tile] does
+    pub fn serialize<S: Serializer>(hand: &[Suit], serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(hand.len()))?;
+        for tile in hand {
+            seq.serialize_element(&super::tile::to_str::<S::Error>(tile)?)?;
+        }
+        seq.end()
+    }
+
+    /// Reads each tile like [super::tile] does
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Suit>, D::Error> {
+        Vec::<std::borrow::Cow<str>>::deserialize(deserializer)?
+            .iter()
+            .map(|tile| super::tile::from_str(tile))
+            .collect()
+    }
+}
+
+/// A single tile in MPSZ notation, e.g. `5m` or `0p` for a red five
+pub mod tile {
+    use ::serde::de::Error;
+    use ::serde::{ser, Deserialize, Deserializer, Serializer};
+
+    use crate::*;
+
+    /// Writes the tile with [Suit::to_mpsz], tiles that aren't [valid](Suit::is_valid)
+    /// are an error since they could never be read back
+    pub fn serialize<S: Serializer>(tile: &Suit, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&to_str::<S::Error>(tile)?)
+    }
+
+    /// Reads a tile written by [serialize], anything but exactly one tile is an error
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Suit, D::Error> {
+        from_str(&<std::borrow::Cow<str>>::deserialize(deserializer)?)
+    }
+
+    pub(super) fn to_str<E: ser::Error>(tile: &Suit) -> Result<String, E> {
+        match tile.is_valid() {
+            true => Ok(Suit::to_mpsz(&[*tile])),
+            false => Err(ser::Error::custom(DecodeErr::InvalidTile { tile: *tile })),
+        }
+    }
+
+    pub(super) fn from_str<E: Error>(text: &str) -> Result<Suit, E> {
+        match Suit::from_mpsz(text).map_err(Error::custom)?[..] {
+            [tile] => Ok(tile),
+            _ => Err(Error::custom(format!(
+                "expected a single tile, found {text:?}"
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ::serde::{Deserialize, Serialize};
+
+    use super::{mpsz, text, tile};
+    use crate::*;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Stored {
+        #[serde(with = "text")]
+        hand: Vec<Suit>,
+        #[serde(with = "mpsz")]
+        pond: Vec<Suit>,
+        #[serde(with = "tile")]
+        tile: Suit,
+    }
+
+    #[test]
+    fn serializes_structured_tiles() {
+        let hand = vec![Suit::Dots(RED_FIVE), Suit::Wind(Wind::East), Suit::Dragon(Dragon::Green)];
+        let json = serde_json::to_string(&hand).unwrap();
+
+        assert_eq!(json, r#"[{"Dots":10},{"Wind":"East"},{"Dragon":"Green"}]"#);
+        assert_eq!(serde_json::from_str::<Vec<Suit>>(&json).unwrap(), hand);
+    }
+
+    #[test]
+    fn round_trips_every_representation() {
+        let stored = Stored {
+            hand: Suit::from_mpsz("1230m406p789s11z").unwrap(),
+            pond: Suit::from_mpsz("0s19p7z").unwrap(),
+            tile: Suit::Wind(Wind::North),
+        };
+        let json = serde_json::to_string(&stored).unwrap();
+
+        assert!(json.contains(r#""pond":["0s","1p","9p","7z"],"tile":"4z""#));
+        assert_eq!(serde_json::from_str::<Stored>(&json).unwrap(), stored);
+    }
+
+    #[test]
+    fn rejects_bad_strings() {
+        let parse =
+            |json: &str| serde_json::from_str::<Stored>(json).map_err(|err| err.to_string());
+
+        assert!(parse(r#"{"hand":"!","pond":[],"tile":"1m"}"#).is_err());
+        assert!(parse(r#"{"hand":"","pond":["12m"],"tile":"1m"}"#)
+            .unwrap_err()
+            .contains("expected a single tile"));
+        assert!(parse(r#"{"hand":"","pond":[],"tile":"8z"}"#).is_err());
+    }
+
+    #[test]
+    fn refuses_to_write_invalid_tiles() {
+        let stored = Stored {
+            hand: vec![],
+            pond: vec![Suit::Bamboo(1), Suit::Dots(0)],
+            tile: Suit::Bamboo(1),
+        };
+        assert!(serde_json::to_string(&stored)
+            .unwrap_err()
+            .to_string()
+            .contains("is not a valid tile"));
+
+        let stored = Stored {
+            pond: vec![],
+            tile: Suit::Characters(12),
+            ..stored
+        };
+        assert!(serde_json::to_string(&stored).is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_structured_tiles() {
+        let parse = |json: &str| serde_json::from_str::<Suit>(json).map_err(|err| err.to_string());
+
+        assert_eq!(parse(r#"{"Bamboo":9}"#), Ok(Suit::Bamboo(9)));
+        assert!(parse(r#"{"Dots":0}"#).is_err());
+        assert!(parse(r#"{"Dots":200}"#)
+            .unwrap_err()
+            .contains("is not a valid tile"));
+        assert!(parse(r#"{"Wind":"Centre"}"#).is_err());
+    }
+}
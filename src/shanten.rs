@@ -0,0 +1,220 @@
+use std::collections::HashSet;
+
+use crate::*;
+
+const TERMINALS_AND_HONOURS: [usize; 13] = [0, 8, 9, 17, 18, 26, 27, 28, 29, 30, 31, 32, 33];
+
+/// 向聴数 _(shanten)_,
+/// how many tiles a hand is away from 聴牌 _(tenpai)_
+///
+/// `0` means the hand is waiting on a tile and `-1` means it's already complete.
+/// Hands of 14, 11, 8... tiles are counted as if the best tile was discarded.
+///
+/// ```rust
+/// # use mahjong_encoding::*;
+/// let hand = Suit::from_mpsz("123m456p789s1122z").unwrap();
+/// let shanten = Shanten::of(&hand).unwrap();
+///
+/// assert_eq!(shanten.regular, 0);
+/// assert_eq!(shanten.seven_pairs, Some(4));
+/// assert_eq!(shanten.min(), 0);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Shanten {
+    /// Shanten for four melds and a pair
+    pub regular: i8,
+    /// 七対子 _(chiitoitsu)_ shanten, only for hands without called melds
+    pub seven_pairs: Option<i8>,
+    /// 国士無双 _(kokushi musou)_ shanten, only for hands without called melds
+    pub thirteen_orphans: Option<i8>,
+}
+
+impl Shanten {
+    /// Works out the shanten of the concealed part of a hand, called melds are
+    /// inferred from how many tiles are left. Can throw a [DecodeErr]
+    pub fn of(hand: &[Suit]) -> Result<Shanten, DecodeErr> {
+        Shanten::of_counts(&TileCounts::from_tiles(hand)?)
+    }
+
+    /// Same as [Shanten::of], for a hand that has already been counted
+    pub fn of_counts(counts: &TileCounts) -> Result<Shanten, DecodeErr> {
+        let length = counts.len();
+        if length == 0 || length > 14 || length.is_multiple_of(3) {
+            return Err(DecodeErr::InvalidHandLength { length });
+        }
+
+        let called = 4 - length / 3;
+        let closed = called == 0;
+
+        Ok(Shanten {
+            regular: regular(counts.counts(), called as i8),
+            seven_pairs: closed.then(|| seven_pairs(counts.counts())),
+            thirteen_orphans: closed.then(|| thirteen_orphans(counts.counts())),
+        })
+    }
+
+    /// The lowest shanten across all the forms
+    pub fn min(&self) -> i8 {
+        [self.seven_pairs, self.thirteen_orphans]
+            .into_iter()
+            .flatten()
+            .fold(self.regular, i8::min)
+    }
+}
+
+/// `8 - 2 * melds - partial melds - pair`, where melds and partial melds together
+/// can't be more than four
+fn regular(counts: &[u8; TILE_KINDS], called: i8) -> i8 {
+    let groups = [
+        blocks(&counts[0..9], false),
+        blocks(&counts[9..18], false),
+        blocks(&counts[18..27], false),
+        blocks(&counts[27..34], true),
+    ];
+
+    let mut best = 8;
+    for a in &groups[0] {
+        for b in &groups[1] {
+            for c in &groups[2] {
+                for d in &groups[3] {
+                    let pair = a.2 + b.2 + c.2 + d.2;
+                    if pair > 1 {
+                        continue;
+                    }
+
+                    let melds = called + a.0 + b.0 + c.0 + d.0;
+                    let partial = (a.1 + b.1 + c.1 + d.1).min(4 - melds);
+                    best = best.min(8 - 2 * melds - partial - pair);
+                }
+            }
+        }
+    }
+
+    best
+}
+
+/// Every `(melds, partial melds, pair)` a single suit can be split into
+fn blocks(counts: &[u8], honours: bool) -> HashSet<(i8, i8, i8)> {
+    let mut found = HashSet::new();
+    let mut counts = counts.to_vec();
+    walk(&mut counts, 0, honours, (0, 0, 0), &mut found);
+    found
+}
+
+fn walk(
+    counts: &mut [u8],
+    start: usize,
+    honours: bool,
+    (melds, partial, pair): (i8, i8, i8),
+    found: &mut HashSet<(i8, i8, i8)>,
+) {
+    let Some(i) = (start..counts.len()).find(|i| counts[*i] > 0) else {
+        found.insert((melds, partial, pair));
+        return;
+    };
+
+    let mut take = |counts: &mut [u8], tiles: &[usize], blocks: (i8, i8, i8)| {
+        tiles.iter().for_each(|tile| counts[*tile] -= 1);
+        walk(counts, i, honours, blocks, found);
+        tiles.iter().for_each(|tile| counts[*tile] += 1);
+    };
+
+    if counts[i] >= 3 {
+        take(counts, &[i, i, i], (melds + 1, partial, pair));
+    }
+    if !honours && i + 2 < counts.len() && counts[i + 1] > 0 && counts[i + 2] > 0 {
+        take(counts, &[i, i + 1, i + 2], (melds + 1, partial, pair));
+    }
+    if counts[i] >= 2 {
+        if pair == 0 {
+            take(counts, &[i, i], (melds, partial, 1));
+        }
+        take(counts, &[i, i], (melds, partial + 1, pair));
+    }
+    if !honours && i + 1 < counts.len() && counts[i + 1] > 0 {
+        take(counts, &[i, i + 1], (melds, partial + 1, pair));
+    }
+    if !honours && i + 2 < counts.len() && counts[i + 2] > 0 {
+        take(counts, &[i, i + 2], (melds, partial + 1, pair));
+    }
+    take(counts, &[i], (melds, partial, pair));
+}
+
+fn seven_pairs(counts: &[u8; TILE_KINDS]) -> i8 {
+    let pairs = counts.iter().filter(|count| **count >= 2).count() as i8;
+    let kinds = counts.iter().filter(|count| **count >= 1).count() as i8;
+
+    6 - pairs + (7 - kinds).max(0)
+}
+
+fn thirteen_orphans(counts: &[u8; TILE_KINDS]) -> i8 {
+    let kinds = TERMINALS_AND_HONOURS
+        .iter()
+        .filter(|index| counts[**index] >= 1)
+        .count() as i8;
+    let pair = TERMINALS_AND_HONOURS
+        .iter()
+        .any(|index| counts[*index] >= 2);
+
+    13 - kinds - pair as i8
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn shanten(hand: &str) -> Shanten {
+        Shanten::of(&Suit::from_mpsz(hand).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn complete_hands_are_minus_one() {
+        assert_eq!(shanten("123m456p789s11122z").regular, -1);
+        assert_eq!(shanten("1122m3344p5566s77z").seven_pairs, Some(-1));
+        assert_eq!(shanten("19m19p19s12345677z").thirteen_orphans, Some(-1));
+    }
+
+    #[test]
+    fn counts_regular_shanten() {
+        assert_eq!(shanten("123m456p789s1122z").regular, 0);
+        assert_eq!(shanten("123m456p789s1234z").regular, 2);
+        assert_eq!(shanten("147m258p369s1234z").regular, 8);
+        assert_eq!(shanten("11m22p33s44z5566z7z").regular, 3);
+    }
+
+    #[test]
+    fn counts_seven_pairs_and_thirteen_orphans() {
+        let hand = shanten("1122m3344p5566s7z");
+        assert_eq!(hand.seven_pairs, Some(0));
+        assert_eq!(hand.min(), 0);
+
+        let hand = shanten("19m19p19s1234567z");
+        assert_eq!(hand.thirteen_orphans, Some(0));
+        assert_eq!(hand.min(), 0);
+
+        // four of a kind can't be two pairs
+        assert_eq!(shanten("1111m3344p5566s7z").seven_pairs, Some(2));
+    }
+
+    #[test]
+    fn supports_called_melds() {
+        let hand = shanten("123m456p1z");
+        assert_eq!(hand.regular, 0);
+        assert_eq!(hand.seven_pairs, None);
+        assert_eq!(hand.thirteen_orphans, None);
+
+        assert_eq!(shanten("1z").regular, 0);
+        assert_eq!(shanten("11z").regular, -1);
+        assert_eq!(shanten("12m").regular, 0);
+    }
+
+    #[test]
+    fn rejects_impossible_lengths() {
+        let hand = Suit::from_mpsz("123m456p789s123z").unwrap();
+
+        assert_eq!(
+            Shanten::of(&hand),
+            Err(DecodeErr::InvalidHandLength { length: 12 })
+        );
+    }
+}
@@ -0,0 +1,782 @@
+//! Tenhou's JSON game logs, the format read by the `tenhou.net/6` viewer.
+//!
+//! Tiles are two digit codes, the suit then the rank: `11`–`19` for 萬子 _(manzu)_,
+//! `21`–`29` for 餅子 _(pinzu)_, `31`–`39` for 索子 _(so-zu)_ and `41`–`47` for the
+//! honours. The red fives are `51`, `52` and `53`.
+
+use std::fmt;
+
+use crate::json::Json;
+use crate::*;
+
+/// Discard code for a tile thrown straight from the draw
+const TSUMOGIRI: u8 = 60;
+
+const WINDS: [Wind; 4] = [Wind::East, Wind::South, Wind::West, Wind::North];
+const HONOURS: [Suit; 7] = [
+    Suit::Wind(Wind::East),
+    Suit::Wind(Wind::South),
+    Suit::Wind(Wind::West),
+    Suit::Wind(Wind::North),
+    Suit::Dragon(Dragon::White),
+    Suit::Dragon(Dragon::Green),
+    Suit::Dragon(Dragon::Red),
+];
+
+impl Suit {
+    /// The tile's code in Tenhou's JSON logs, tiles that aren't
+    /// [valid](Suit::is_valid) have none
+    ///
+    /// ```
+    /// # use mahjong_encoding::*;
+    /// assert_eq!(Suit::Characters(1).to_tenhou_code(), Some(11));
+    /// assert_eq!(Suit::Dots(RED_FIVE).to_tenhou_code(), Some(52));
+    /// assert_eq!(Suit::Dragon(Dragon::Red).to_tenhou_code(), Some(47));
+    /// assert_eq!(Suit::Bamboo(0).to_tenhou_code(), None);
+    /// ```
+    pub fn to_tenhou_code(&self) -> Option<u8> {
+        if !self.is_valid() {
+            return None;
+        }
+
+        Some(match *self {
+            Suit::Characters(RED_FIVE) => 51,
+            Suit::Dots(RED_FIVE) => 52,
+            Suit::Bamboo(RED_FIVE) => 53,
+            Suit::Characters(n) => 10 + n,
+            Suit::Dots(n) => 20 + n,
+            Suit::Bamboo(n) => 30 + n,
+            honour => 41 + HONOURS.iter().position(|other| *other == honour)? as u8,
+        })
+    }
+
+    /// The tile for a code in Tenhou's JSON logs
+    ///
+    /// ```
+    /// # use mahjong_encoding::*;
+    /// assert_eq!(Suit::from_tenhou_code(35), Some(Suit::Bamboo(5)));
+    /// assert_eq!(Suit::from_tenhou_code(53), Some(Suit::Bamboo(RED_FIVE)));
+    /// assert_eq!(Suit::from_tenhou_code(41), Some(Suit::Wind(Wind::East)));
+    /// assert_eq!(Suit::from_tenhou_code(20), None);
+    /// ```
+    pub fn from_tenhou_code(code: u8) -> Option<Suit> {
+        let rank = code % 10;
+
+        Some(match (code / 10, rank) {
+            (1, 1..=9) => Suit::Characters(rank),
+            (2, 1..=9) => Suit::Dots(rank),
+            (3, 1..=9) => Suit::Bamboo(rank),
+            (4, 1..=7) => HONOURS[rank as usize - 1],
+            (5, 1) => Suit::Characters(RED_FIVE),
+            (5, 2) => Suit::Dots(RED_FIVE),
+            (5, 3) => Suit::Bamboo(RED_FIVE),
+            _ => return None,
+        })
+    }
+}
+
+/// A whole game in Tenhou's JSON log format. Fields the crate doesn't know about
+/// are dropped when reading.
+///
+/// ```
+/// # use mahjong_encoding::*;
+/// let log = r#"{"title":["",""],"name":["A","B","C","D"],"rule":{"disp":"般南喰赤","aka":1},
+///     "log":[[[0,0,0],[25000,25000,25000,25000],[14],[],
+///         [11,12,13],[47],[60], [21,22,23],[],[], [31,32,33],[],[], [41,42,43],[],[],
+///         ["流局",[0,0,0,0]]]]}"#;
+/// let game = TenhouLog::from_json(log).unwrap();
+///
+/// assert_eq!(game.rounds[0].draws[0], [Take::Tile(Suit::Dragon(Dragon::Red))]);
+/// assert_eq!(game.rounds[0].discards[0], [Dahai::Tsumogiri]);
+/// assert_eq!(TenhouLog::from_json(&game.to_json()), Ok(game));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TenhouLog {
+    /// The lines shown above the table in the viewer
+    pub title: Vec<String>,
+    /// The players' names, in seat order from the first dealer
+    pub players: Vec<String>,
+    /// Tenhou's short description of the rules, e.g. `般南喰赤`
+    pub rule: String,
+    /// How many red fives there are in 萬子 _(manzu)_, 餅子 _(pinzu)_ and
+    /// 索子 _(so-zu)_
+    pub red_fives: [u8; 3],
+    /// Every round, in order
+    pub rounds: Vec<TenhouRound>,
+}
+
+/// 局 _(kyoku)_, a single round of a [TenhouLog]. Each player's draws and discards
+/// are listed separately, the viewer pairs them back up as it plays the round.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TenhouRound {
+    /// 場風 _(bakaze)_, the wind of the round
+    pub round_wind: Wind,
+    /// Which round of that wind this is, from `1`
+    pub number: u8,
+    /// 本場 _(honba)_, repeat counters on the table
+    pub honba: u8,
+    /// 供託 _(kyoutaku)_, riichi sticks left on the table
+    pub riichi_sticks: u8,
+    /// Each player's points at the start of the round
+    pub scores: [i32; 4],
+    /// ドラ表示牌 _(dora hyoujihai)_, in the order they were revealed
+    pub dora_indicators: Vec<Suit>,
+    /// 裏ドラ表示牌 _(ura dora hyoujihai)_, only shown after a riichi win
+    pub ura_dora_indicators: Vec<Suit>,
+    /// Each player's starting hand
+    pub hands: [Vec<Suit>; 4],
+    /// Each player's draws and calls, in order
+    pub draws: [Vec<Take>; 4],
+    /// Each player's discards and kans from the hand, in order
+    pub discards: [Vec<Dahai>; 4],
+    /// How the round ended
+    pub result: RoundResult,
+}
+
+/// A tile taken into the hand
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Take {
+    /// A tile drawn from the wall
+    Tile(Suit),
+    /// A chi, pon or open kan called from a discard
+    Call(Meld),
+}
+
+/// 打牌 _(dahai)_, a player's action after taking a tile
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Dahai {
+    /// A tile discarded from the hand
+    Tile(Suit),
+    /// ツモ切り _(tsumogiri)_, the drawn tile discarded straight away
+    Tsumogiri,
+    /// A riichi declared by discarding a tile from the hand
+    Riichi(Suit),
+    /// A riichi declared by discarding the drawn tile
+    RiichiTsumogiri,
+    /// A closed or added kan, declared instead of discarding
+    Kan(Meld),
+    /// Nothing was discarded, used after an open kan
+    Pass,
+}
+
+/// How a [TenhouRound] ended
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RoundResult {
+    /// 和了 _(agari)_, one win or several on the same discard
+    Agari(Vec<TenhouWin>),
+    /// The round ended without a winner
+    Ryuukyoku {
+        /// Tenhou's name for the draw, e.g. `流局` or `九種九牌`
+        reason: String,
+        /// How many points each player gained or lost, if anyone did
+        score_changes: Option<[i32; 4]>,
+    },
+}
+
+/// A single win of a [RoundResult::Agari]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TenhouWin {
+    /// How many points each player gained or lost, including honba and sticks
+    pub score_changes: [i32; 4],
+    /// Who won
+    pub who: usize,
+    /// Who dealt in, the same as `who` for a tsumo
+    pub from_who: usize,
+    /// 包 _(pao)_, who pays for a yakuman they fed, the same as `who` if nobody does
+    pub pao_who: usize,
+    /// Tenhou's summary of the score, e.g. `30符2飜2000点`
+    pub summary: String,
+    /// Tenhou's name for each yaku with its han, e.g. `立直(1飜)`
+    pub yaku: Vec<String>,
+}
+
+impl TenhouLog {
+    /// Reads a JSON log. Can throw a [DecodeErr]
+    pub fn from_json(input: &str) -> Result<TenhouLog, DecodeErr> {
+        let json = Json::parse(input)?;
+        let rule = json.get("rule");
+        let aka = |key| match rule.and_then(|rule| rule.get(key)) {
+            Some(value) => value
+                .as_i64()
+                .and_then(|count| u8::try_from(count).ok())
+                .map(Some)
+                .ok_or(DecodeErr::InvalidField { field: "rule" }),
+            None => Ok(None),
+        };
+
+        let all = aka("aka")?.unwrap_or(0);
+        let red_fives = [
+            aka("aka51")?.unwrap_or(all),
+            aka("aka52")?.unwrap_or(all),
+            aka("aka53")?.unwrap_or(all),
+        ];
+
+        Ok(TenhouLog {
+            title: strings(json.get("title"), "title")?,
+            players: strings(json.get("name"), "name")?,
+            rule: rule
+                .and_then(|rule| rule.get("disp"))
+                .and_then(Json::as_str)
+                .unwrap_or("")
+                .to_string(),
+            red_fives,
+            rounds: array(json.get("log"), "log")?
+                .iter()
+                .map(TenhouRound::from_json)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    /// Writes the log as JSON that the viewer can load
+    pub fn to_json(&self) -> String {
+        let strings = |values: &[String]| {
+            Json::Array(values.iter().map(|value| value.as_str().into()).collect())
+        };
+
+        let mut rule = vec![("disp".to_string(), self.rule.as_str().into())];
+        match self.red_fives {
+            [a, b, c] if a == b && b == c => rule.push(("aka".to_string(), (a as i64).into())),
+            counts => {
+                for (key, count) in ["aka51", "aka52", "aka53"].iter().zip(counts) {
+                    rule.push((key.to_string(), (count as i64).into()));
+                }
+            }
+        }
+
+        Json::Object(vec![
+            ("title".to_string(), strings(&self.title)),
+            ("name".to_string(), strings(&self.players)),
+            ("rule".to_string(), Json::Object(rule)),
+            (
+                "log".to_string(),
+                Json::Array(self.rounds.iter().map(TenhouRound::to_json).collect()),
+            ),
+        ])
+        .to_string()
+    }
+}
+
+impl fmt::Display for TenhouLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_json())
+    }
+}
+
+impl TenhouRound {
+    fn from_json(json: &Json) -> Result<TenhouRound, DecodeErr> {
+        let invalid = |field| move || DecodeErr::InvalidField { field };
+        let parts = json.as_array().ok_or_else(invalid("log"))?;
+        if parts.len() != 17 {
+            return Err(invalid("log")());
+        }
+
+        let [kyoku @ 0..16, honba, riichi_sticks] = integers(&parts[0], "round")?[..] else {
+            return Err(invalid("round")());
+        };
+        let scores = integers(&parts[1], "scores")?
+            .iter()
+            .map(|score| *score as i32)
+            .collect::<Vec<_>>();
+        let seats = |offset: usize| [0, 1, 2, 3].map(|seat| &parts[4 + seat * 3 + offset]);
+
+        let hands = seats(0).map(tiles);
+        let draws = seats(1).map(|json| items(json, "take", take));
+        let discards = seats(2).map(|json| items(json, "dahai", dahai));
+        let [a, b, c, d] = hands;
+        let hands = [a?, b?, c?, d?];
+        let [a, b, c, d] = draws;
+        let draws = [a?, b?, c?, d?];
+        let [a, b, c, d] = discards;
+        let discards = [a?, b?, c?, d?];
+
+        Ok(TenhouRound {
+            round_wind: WINDS[kyoku as usize / 4],
+            number: (kyoku % 4) as u8 + 1,
+            honba: u8::try_from(honba).map_err(|_| invalid("round")())?,
+            riichi_sticks: u8::try_from(riichi_sticks).map_err(|_| invalid("round")())?,
+            scores: scores.try_into().map_err(|_| invalid("scores")())?,
+            dora_indicators: tiles(&parts[2])?,
+            ura_dora_indicators: tiles(&parts[3])?,
+            hands,
+            draws,
+            discards,
+            result: result(&parts[16])?,
+        })
+    }
+
+    fn to_json(&self) -> Json {
+        let wind = WINDS
+            .iter()
+            .position(|wind| *wind == self.round_wind)
+            .unwrap();
+        let kyoku = (wind * 4) as i64 + self.number as i64 - 1;
+        let integers = |values: &[i64]| Json::Array(values.iter().map(|v| (*v).into()).collect());
+        let tiles = |tiles: &[Suit]| Json::Array(tiles.iter().map(code).collect());
+
+        let mut parts = vec![
+            integers(&[kyoku, self.honba as i64, self.riichi_sticks as i64]),
+            integers(&self.scores.map(|score| score as i64)),
+            tiles(&self.dora_indicators),
+            tiles(&self.ura_dora_indicators),
+        ];
+        for seat in 0..4 {
+            parts.push(tiles(&self.hands[seat]));
+            parts.push(Json::Array(
+                self.draws[seat].iter().map(Take::to_json).collect(),
+            ));
+            parts.push(Json::Array(
+                self.discards[seat].iter().map(Dahai::to_json).collect(),
+            ));
+        }
+        parts.push(self.result.to_json());
+
+        Json::Array(parts)
+    }
+}
+
+impl Take {
+    fn to_json(&self) -> Json {
+        match self {
+            Take::Tile(tile) => code(tile),
+            Take::Call(meld) => Json::String(call(meld)),
+        }
+    }
+}
+
+impl Dahai {
+    fn to_json(&self) -> Json {
+        match self {
+            Dahai::Tile(tile) => code(tile),
+            Dahai::Tsumogiri => (TSUMOGIRI as i64).into(),
+            Dahai::Riichi(tile) => Json::String(format!("r{}", code(tile))),
+            Dahai::RiichiTsumogiri => Json::String(format!("r{TSUMOGIRI}")),
+            Dahai::Kan(meld) => Json::String(call(meld)),
+            Dahai::Pass => 0.into(),
+        }
+    }
+}
+
+impl RoundResult {
+    fn to_json(&self) -> Json {
+        match self {
+            RoundResult::Agari(wins) => {
+                let mut parts = vec!["和了".into()];
+                for win in wins {
+                    parts.push(Json::Array(
+                        win.score_changes
+                            .iter()
+                            .map(|v| (*v as i64).into())
+                            .collect(),
+                    ));
+
+                    let mut details = vec![
+                        (win.who as i64).into(),
+                        (win.from_who as i64).into(),
+                        (win.pao_who as i64).into(),
+                        win.summary.as_str().into(),
+                    ];
+                    details.extend(win.yaku.iter().map(|yaku| yaku.as_str().into()));
+                    parts.push(Json::Array(details));
+                }
+                Json::Array(parts)
+            }
+            RoundResult::Ryuukyoku {
+                reason,
+                score_changes,
+            } => {
+                let mut parts = vec![reason.as_str().into()];
+                if let Some(changes) = score_changes {
+                    parts.push(Json::Array(
+                        changes.iter().map(|v| (*v as i64).into()).collect(),
+                    ));
+                }
+                Json::Array(parts)
+            }
+        }
+    }
+}
+
+fn code(tile: &Suit) -> Json {
+    (tile.to_tenhou_code().unwrap_or(0) as i64).into()
+}
+
+fn tile(code: i64) -> Result<Suit, DecodeErr> {
+    u8::try_from(code)
+        .ok()
+        .and_then(Suit::from_tenhou_code)
+        .ok_or(DecodeErr::InvalidTileCode {
+            code: code.clamp(0, u32::MAX as i64) as u32,
+        })
+}
+
+fn tiles(json: &Json) -> Result<Vec<Suit>, DecodeErr> {
+    integers(json, "tiles")?.into_iter().map(tile).collect()
+}
+
+fn take(json: &Json) -> Result<Take, DecodeErr> {
+    match json {
+        Json::String(text) => Ok(Take::Call(meld(text)?)),
+        _ => Ok(Take::Tile(tile(integer(json, "take")?)?)),
+    }
+}
+
+fn dahai(json: &Json) -> Result<Dahai, DecodeErr> {
+    let text = match json {
+        Json::String(text) => text,
+        _ => {
+            return match integer(json, "dahai")? {
+                0 => Ok(Dahai::Pass),
+                code if code == TSUMOGIRI as i64 => Ok(Dahai::Tsumogiri),
+                code => Ok(Dahai::Tile(tile(code)?)),
+            }
+        }
+    };
+
+    let Some(code) = text.strip_prefix('r') else {
+        return Ok(Dahai::Kan(meld(text)?));
+    };
+    match code.parse::<i64>() {
+        Ok(code) if code == TSUMOGIRI as i64 => Ok(Dahai::RiichiTsumogiri),
+        Ok(code) => Ok(Dahai::Riichi(tile(code)?)),
+        Err(_) => Err(DecodeErr::InvalidField { field: "dahai" }),
+    }
+}
+
+/// Reads a call such as `c275226` or `3737p37`. The letter says what kind of meld it
+/// is and where it sits says who the tile was called from: at the start for the
+/// player on the left, after the first tile for the player across and at the end
+/// for the player on the right. The code after the letter is the called tile, or
+/// for an added kan the added tile followed by the called one.
+fn meld(text: &str) -> Result<Meld, DecodeErr> {
+    let invalid = DecodeErr::InvalidField { field: "call" };
+    let position = text
+        .find(|c: char| c.is_ascii_alphabetic())
+        .ok_or(invalid)?;
+    let letter = text.as_bytes()[position];
+    let digits = text[..position].to_string() + &text[position + 1..];
+
+    if !position.is_multiple_of(2) || !digits.len().is_multiple_of(2) || !digits.is_ascii() {
+        return Err(invalid);
+    }
+    let tiles = (0..digits.len())
+        .step_by(2)
+        .map(|i| digits[i..i + 2].parse().map_err(|_| invalid).and_then(tile))
+        .collect::<Result<Vec<_>, _>>()?;
+    let called = *tiles.get(position / 2).ok_or(invalid)?;
+
+    match (letter, position) {
+        (b'c', 0) => Meld::called(MeldKind::Chi, tiles, called, Seat::Left),
+        (b'p', _) => Meld::called(MeldKind::Pon, tiles, called, seat(position, 4)?),
+        (b'm', _) => Meld::called(MeldKind::OpenKan, tiles, called, seat(position, 6)?),
+        (b'k', _) => {
+            let mut pon = tiles.clone();
+            let added = pon.remove(position / 2);
+            let called = *pon.get(position / 2).ok_or(invalid)?;
+
+            Meld::called(MeldKind::Pon, pon, called, seat(position, 4)?)?.upgrade(added)
+        }
+        (b'a', 6) => Meld::new(MeldKind::ClosedKan, tiles),
+        _ => Err(invalid),
+    }
+}
+
+/// Who a tile was called from, given where the letter sits in a call
+fn seat(position: usize, right: usize) -> Result<Seat, DecodeErr> {
+    match position {
+        0 => Ok(Seat::Left),
+        2 => Ok(Seat::Across),
+        _ if position == right => Ok(Seat::Right),
+        _ => Err(DecodeErr::InvalidField { field: "call" }),
+    }
+}
+
+/// Writes a meld the way [meld] reads it. An open meld without a recorded claim is
+/// written as if its first tile was called from the player on the left
+fn call(meld: &Meld) -> String {
+    let codes = |tiles: &[Suit]| {
+        tiles
+            .iter()
+            .map(|tile| tile.to_tenhou_code().unwrap_or(0).to_string())
+            .collect::<Vec<_>>()
+    };
+
+    let tiles = meld.tiles();
+    let claim = meld.claim().unwrap_or(Claim {
+        tile: tiles[0],
+        from: Seat::Left,
+    });
+    let mut others = tiles.to_vec();
+    if let Some(index) = others.iter().position(|tile| *tile == claim.tile) {
+        others.remove(index);
+    }
+
+    let (letter, called) = match meld.kind() {
+        MeldKind::ClosedKan => {
+            let mut parts = codes(tiles);
+            parts.insert(3, "a".to_string());
+            return parts.concat();
+        }
+        MeldKind::AddedKan => {
+            let index = others.iter().rposition(|tile| !tile.is_red()).unwrap_or(0);
+            let added = others.remove(index);
+            ("k", codes(&[added, claim.tile]).concat())
+        }
+        MeldKind::Chi => ("c", codes(&[claim.tile]).concat()),
+        MeldKind::Pon => ("p", codes(&[claim.tile]).concat()),
+        MeldKind::OpenKan => ("m", codes(&[claim.tile]).concat()),
+    };
+
+    let mut parts = codes(&others);
+    let index = match claim.from {
+        Seat::Left => 0,
+        Seat::Across => 1,
+        Seat::Right => parts.len(),
+    };
+    parts.insert(index, letter.to_string() + &called);
+    parts.concat()
+}
+
+fn result(json: &Json) -> Result<RoundResult, DecodeErr> {
+    let invalid = || DecodeErr::InvalidField { field: "result" };
+    let parts = json.as_array().ok_or_else(invalid)?;
+    let (name, rest) = parts.split_first().ok_or_else(invalid)?;
+    let name = name.as_str().ok_or_else(invalid)?;
+
+    if name != "和了" {
+        return Ok(RoundResult::Ryuukyoku {
+            reason: name.to_string(),
+            score_changes: rest.first().map(score_changes).transpose()?,
+        });
+    }
+
+    let wins = rest
+        .chunks(2)
+        .map(|win| {
+            let [changes, details] = win else {
+                return Err(invalid());
+            };
+            let details = details.as_array().ok_or_else(invalid)?;
+            if details.len() < 4 {
+                return Err(invalid());
+            }
+            let who = |json: &Json| {
+                integer(json, "result")
+                    .and_then(|seat| usize::try_from(seat).map_err(|_| invalid()))
+                    .and_then(|seat| if seat < 4 { Ok(seat) } else { Err(invalid()) })
+            };
+
+            Ok(TenhouWin {
+                score_changes: score_changes(changes)?,
+                who: who(&details[0])?,
+                from_who: who(&details[1])?,
+                pao_who: who(&details[2])?,
+                summary: details[3].as_str().ok_or_else(invalid)?.to_string(),
+                yaku: details[4..]
+                    .iter()
+                    .map(|yaku| yaku.as_str().map(str::to_string).ok_or_else(invalid))
+                    .collect::<Result<_, _>>()?,
+            })
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok(RoundResult::Agari(wins))
+}
+
+fn score_changes(json: &Json) -> Result<[i32; 4], DecodeErr> {
+    integers(json, "result")?
+        .iter()
+        .map(|change| *change as i32)
+        .collect::<Vec<_>>()
+        .try_into()
+        .map_err(|_| DecodeErr::InvalidField { field: "result" })
+}
+
+fn array<'a>(json: Option<&'a Json>, field: &'static str) -> Result<&'a [Json], DecodeErr> {
+    json.and_then(Json::as_array)
+        .ok_or(DecodeErr::InvalidField { field })
+}
+
+fn items<T>(
+    json: &Json,
+    field: &'static str,
+    item: fn(&Json) -> Result<T, DecodeErr>,
+) -> Result<Vec<T>, DecodeErr> {
+    array(Some(json), field)?.iter().map(item).collect()
+}
+
+fn integer(json: &Json, field: &'static str) -> Result<i64, DecodeErr> {
+    json.as_i64().ok_or(DecodeErr::InvalidField { field })
+}
+
+fn integers(json: &Json, field: &'static str) -> Result<Vec<i64>, DecodeErr> {
+    array(Some(json), field)?
+        .iter()
+        .map(|value| integer(value, field))
+        .collect()
+}
+
+fn strings(json: Option<&Json>, field: &'static str) -> Result<Vec<String>, DecodeErr> {
+    array(json, field)?
+        .iter()
+        .map(|value| value.as_str().map(str::to_string))
+        .collect::<Option<_>>()
+        .ok_or(DecodeErr::InvalidField { field })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const LOG: &str = r#"{"title":["table","2026/10/17"],"name":["A","B","C","D"],"rule":{"disp":"般南喰赤","aka":1},"log":[[[5,1,1],[25000,24000,26000,25000],[21,44],[38],[11,12,13,14,15,16,17,18,19,21,22,23,24],[51,"c275226",45],[60,"r19",0],[31,32,33,34,35,36,37,38,39,41,41,42,42],["37p3737","37k373737"],[41,"424242a42"],[43,43,43,44,44,44,45,45,45,46,46,46,47],["434343m43"],[47],[11,11,11,12,12,12,13,13,13,14,14,14,15],[],[],["和了",[0,8000,-8000,0],[1,2,1,"30符4飜8000点","立直(1飜)","ドラ(3飜)"]]],[[6,0,0],[25000,32000,18000,25000],[11],[],[],[],[],[],[],[],[],[],[],[],[],[],["九種九牌"]]]}"#;
+
+    #[test]
+    fn round_trips_every_tile_code() {
+        let mut tiles = (0..TILE_KINDS)
+            .filter_map(Suit::from_index)
+            .collect::<Vec<_>>();
+        tiles.extend(Suit::from_mpsz("0m0p0s").unwrap());
+
+        for tile in tiles {
+            let code = tile.to_tenhou_code().unwrap();
+            assert_eq!(Suit::from_tenhou_code(code), Some(tile));
+        }
+        for code in [0, 10, 19, 20, 48, 50, 54, 60] {
+            assert_eq!(
+                Suit::from_tenhou_code(code).and_then(|tile| tile.to_tenhou_code()),
+                Some(code).filter(|code| *code == 19)
+            );
+        }
+    }
+
+    #[test]
+    fn reads_rounds() {
+        let log = TenhouLog::from_json(LOG).unwrap();
+        let round = &log.rounds[0];
+
+        assert_eq!(log.title, ["table", "2026/10/17"]);
+        assert_eq!(log.players, ["A", "B", "C", "D"]);
+        assert_eq!(log.rule, "般南喰赤");
+        assert_eq!(log.red_fives, [1, 1, 1]);
+        assert_eq!((round.round_wind, round.number), (Wind::South, 2));
+        assert_eq!((round.honba, round.riichi_sticks), (1, 1));
+        assert_eq!(round.scores, [25000, 24000, 26000, 25000]);
+        assert_eq!(Suit::to_mpsz(&round.dora_indicators), "1p4z");
+        assert_eq!(Suit::to_mpsz(&round.hands[0]), "123456789m1234p");
+        assert_eq!(round.draws[0][0], Take::Tile(Suit::Characters(RED_FIVE)));
+        assert_eq!(
+            round.discards[0],
+            [Dahai::Tsumogiri, Dahai::Riichi(Suit::Characters(9)), Dahai::Pass]
+        );
+        assert_eq!(
+            log.rounds[1].result,
+            RoundResult::Ryuukyoku {
+                reason: "九種九牌".to_string(),
+                score_changes: None,
+            }
+        );
+    }
+
+    #[test]
+    fn reads_calls() {
+        let log = TenhouLog::from_json(LOG).unwrap();
+        let round = &log.rounds[0];
+        let meld = |take: &Take| match take {
+            Take::Call(meld) => meld.clone(),
+            take => panic!("{take:?} isn't a call"),
+        };
+
+        let chi = meld(&round.draws[0][1]);
+        assert_eq!(chi.kind(), MeldKind::Chi);
+        assert_eq!(Suit::to_mpsz(chi.tiles()), "067p");
+        assert_eq!(
+            chi.claim(),
+            Some(Claim {
+                tile: Suit::Dots(7),
+                from: Seat::Left
+            })
+        );
+
+        let pon = meld(&round.draws[1][0]);
+        assert_eq!(pon.kind(), MeldKind::Pon);
+        assert_eq!(pon.claim().unwrap().from, Seat::Across);
+
+        let added = meld(&round.draws[1][1]);
+        assert_eq!(added.kind(), MeldKind::AddedKan);
+        assert_eq!(added.claim().unwrap().from, Seat::Across);
+
+        let kan = meld(&round.draws[2][0]);
+        assert_eq!(kan.kind(), MeldKind::OpenKan);
+        assert_eq!(kan.claim().unwrap().from, Seat::Right);
+
+        let Dahai::Kan(closed) = &round.discards[1][1] else {
+            panic!("expected a closed kan");
+        };
+        assert_eq!(closed.kind(), MeldKind::ClosedKan);
+        assert_eq!(Suit::to_mpsz(closed.tiles()), "2222z");
+    }
+
+    #[test]
+    fn reads_wins() {
+        let log = TenhouLog::from_json(LOG).unwrap();
+
+        assert_eq!(
+            log.rounds[0].result,
+            RoundResult::Agari(vec![TenhouWin {
+                score_changes: [0, 8000, -8000, 0],
+                who: 1,
+                from_who: 2,
+                pao_who: 1,
+                summary: "30符4飜8000点".to_string(),
+                yaku: vec!["立直(1飜)".to_string(), "ドラ(3飜)".to_string()],
+            }])
+        );
+    }
+
+    #[test]
+    fn writes_logs_back_unchanged() {
+        let log = TenhouLog::from_json(LOG).unwrap();
+
+        assert_eq!(log.to_json(), LOG);
+        assert_eq!(log.to_string(), LOG);
+    }
+
+    #[test]
+    fn writes_red_fives_per_suit() {
+        let mut log = TenhouLog::from_json(LOG).unwrap();
+        log.red_fives = [1, 2, 0];
+
+        let json = log.to_json();
+        assert!(json.contains(r#""aka51":1,"aka52":2,"aka53":0"#));
+        assert_eq!(TenhouLog::from_json(&json).unwrap().red_fives, [1, 2, 0]);
+    }
+
+    #[test]
+    fn rejects_bad_logs() {
+        assert_eq!(
+            TenhouLog::from_json(&LOG.replace("[51,", "[58,")),
+            Err(DecodeErr::InvalidTileCode { code: 58 })
+        );
+        assert_eq!(
+            TenhouLog::from_json(&LOG.replace("37p3737", "373p737")),
+            Err(DecodeErr::InvalidField { field: "call" })
+        );
+        assert_eq!(
+            TenhouLog::from_json(&LOG.replace("c275226", "c275326")),
+            Err(DecodeErr::InvalidMeld {
+                kind: MeldKind::Chi
+            })
+        );
+        assert!(TenhouLog::from_json(r#"{"log":[]}"#).is_err());
+    }
+
+    #[test]
+    fn rejects_rounds_out_of_range() {
+        for round in ["[-1,0,0]", "[16,0,0]"] {
+            let log = LOG.replace("[6,0,0]", round);
+            assert_eq!(
+                TenhouLog::from_json(&log),
+                Err(DecodeErr::InvalidField { field: "round" })
+            );
+        }
+    }
+}
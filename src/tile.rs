@@ -0,0 +1,199 @@
+use std::fmt;
+
+use crate::*;
+
+/// The rank of a numbered tile, either `1`–`9` or [RED_FIVE]
+///
+/// ```rust
+/// # use mahjong_encoding::*;
+/// assert!(Rank::new(9).is_ok());
+/// assert!(Rank::new(RED_FIVE).is_ok());
+/// assert!(Rank::new(0).is_err());
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Rank(u8);
+
+impl Rank {
+    /// Checks that `rank` refers to a tile that exists. Can throw a [TileErr]
+    pub const fn new(rank: u8) -> Result<Rank, TileErr> {
+        match rank {
+            1..=9 | RED_FIVE => Ok(Rank(rank)),
+            _ => Err(TileErr::InvalidRank { rank }),
+        }
+    }
+
+    /// The raw rank, as used inside [Suit]
+    pub const fn get(self) -> u8 {
+        self.0
+    }
+
+    /// Whether this is [RED_FIVE]
+    pub const fn is_red(self) -> bool {
+        self.0 == RED_FIVE
+    }
+}
+
+impl TryFrom<u8> for Rank {
+    type Error = TileErr;
+
+    fn try_from(rank: u8) -> Result<Rank, TileErr> {
+        Rank::new(rank)
+    }
+}
+
+impl From<Rank> for u8 {
+    fn from(rank: Rank) -> u8 {
+        rank.get()
+    }
+}
+
+/// 牌 _(pai)_,
+/// a [Suit] that is known to be a real tile
+///
+/// A [Suit] can hold any `u8`, a [Tile] can only be made from one that refers to a
+/// tile in the game, so it always encodes to the tile it was made from.
+///
+/// ```rust
+/// # use mahjong_encoding::*;
+/// let tile = Tile::new(Suit::Dots(RED_FIVE)).unwrap();
+/// assert_eq!(tile.suit(), Suit::Dots(RED_FIVE));
+/// assert!(Tile::try_from(Suit::Dots(0x1F)).is_err());
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Tile(Suit);
+
+impl Tile {
+    /// Checks that `suit` refers to a tile that exists. Can throw a [TileErr]
+    pub const fn new(suit: Suit) -> Result<Tile, TileErr> {
+        match suit {
+            Suit::Dots(n) | Suit::Bamboo(n) | Suit::Characters(n) => match Rank::new(n) {
+                Ok(_) => Ok(Tile(suit)),
+                Err(err) => Err(err),
+            },
+            Suit::Wind(_) | Suit::Dragon(_) => Ok(Tile(suit)),
+        }
+    }
+
+    /// A tile from the 餅子 _(pinzu)_
+    pub const fn dots(rank: Rank) -> Tile {
+        Tile(Suit::Dots(rank.get()))
+    }
+
+    /// A tile from the 索子 _(so-zu)_
+    pub const fn bamboo(rank: Rank) -> Tile {
+        Tile(Suit::Bamboo(rank.get()))
+    }
+
+    /// A tile from the 萬子 _(manzu)_
+    pub const fn characters(rank: Rank) -> Tile {
+        Tile(Suit::Characters(rank.get()))
+    }
+
+    /// A 風牌 _(fompai)_
+    pub const fn wind(wind: Wind) -> Tile {
+        Tile(Suit::Wind(wind))
+    }
+
+    /// A 三元牌 _(sangempai)_
+    pub const fn dragon(dragon: Dragon) -> Tile {
+        Tile(Suit::Dragon(dragon))
+    }
+
+    /// The [Suit] this tile was made from
+    pub const fn suit(self) -> Suit {
+        self.0
+    }
+}
+
+impl TryFrom<Suit> for Tile {
+    type Error = TileErr;
+
+    fn try_from(suit: Suit) -> Result<Tile, TileErr> {
+        Tile::new(suit)
+    }
+}
+
+impl From<Tile> for Suit {
+    fn from(tile: Tile) -> Suit {
+        tile.suit()
+    }
+}
+
+impl ToByte for Tile {
+    fn to_byte(&self) -> u8 {
+        self.0.to_byte()
+    }
+}
+
+impl Suit {
+    /// Whether this refers to a tile that exists, see [Tile]
+    ///
+    /// ```
+    /// # use mahjong_encoding::*;
+    /// assert!(Suit::Bamboo(1).is_valid());
+    /// assert!(!Suit::Bamboo(0).is_valid());
+    /// ```
+    pub const fn is_valid(&self) -> bool {
+        Tile::new(*self).is_ok()
+    }
+}
+
+/// Errors that can be thrown when creating a [Rank] or a [Tile]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TileErr {
+    /// Numbered tiles only go from `1` to `9`, plus [RED_FIVE]
+    InvalidRank {
+        /// The rank that was given
+        rank: u8,
+    },
+}
+
+impl fmt::Display for TileErr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TileErr::InvalidRank { rank } => {
+                write!(f, "rank {rank} must be between 1 and 9, or RED_FIVE")
+            }
+        }
+    }
+}
+
+impl std::error::Error for TileErr {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn accepts_every_rank_in_the_game() {
+        for rank in (1..=9).chain([RED_FIVE]) {
+            assert_eq!(Rank::try_from(rank).map(u8::from), Ok(rank));
+        }
+    }
+
+    #[test]
+    fn rejects_out_of_range_ranks() {
+        for rank in [0, 0xB, 0xF, 0x1F, u8::MAX] {
+            assert_eq!(Rank::new(rank), Err(TileErr::InvalidRank { rank }));
+            assert!(Tile::new(Suit::Dots(rank)).is_err());
+            assert!(Tile::new(Suit::Bamboo(rank)).is_err());
+            assert!(Tile::new(Suit::Characters(rank)).is_err());
+        }
+    }
+
+    #[test]
+    fn encodes_like_the_suit_it_was_made_from() {
+        let tile = Tile::characters(Rank::new(7).unwrap());
+
+        assert_eq!(tile.to_byte(), Suit::Characters(7).to_byte());
+        assert_eq!(Suit::from(tile), Suit::Characters(7));
+    }
+
+    #[test]
+    fn decodes_only_valid_tiles() {
+        for tile in lookup::INDEX.into_iter().flatten() {
+            assert!(tile.is_valid(), "{tile:?} should not be decodable");
+        }
+    }
+}
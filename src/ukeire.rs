@@ -0,0 +1,214 @@
+use crate::*;
+
+/// A tile that would improve a hand, and how many copies of it are still unseen
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Acceptance {
+    /// The tile to draw
+    pub tile: Suit,
+    /// Copies that aren't in the hand or visible on the table
+    pub remaining: u8,
+}
+
+/// 受け入れ _(ukeire)_,
+/// every tile that would bring a hand closer to winning
+///
+/// ```rust
+/// # use mahjong_encoding::*;
+/// let hand = Suit::from_mpsz("123m456p789s23m11z").unwrap();
+/// let visible = Suit::from_mpsz("44m").unwrap();
+/// let ukeire = Ukeire::of(&hand, &visible).unwrap();
+///
+/// assert_eq!(ukeire.shanten, 0);
+/// assert_eq!(ukeire.total(), 3 + 2);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Ukeire {
+    /// The shanten of the hand, see [Shanten::min]
+    pub shanten: i8,
+    /// The tiles that lower the shanten, in canonical order
+    pub tiles: Vec<Acceptance>,
+}
+
+impl Ukeire {
+    /// Finds the tiles that would lower the shanten of a hand waiting to draw, i.e.
+    /// one of 13, 10, 7... tiles. Tiles in the hand and in `visible` (discards, called
+    /// melds, dora indicators...) are taken out of the remaining counts.
+    /// Can throw a [DecodeErr]
+    pub fn of(hand: &[Suit], visible: &[Suit]) -> Result<Ukeire, DecodeErr> {
+        let counts = TileCounts::from_tiles(hand)?;
+        if counts.len() % 3 != 1 {
+            return Err(DecodeErr::InvalidHandLength {
+                length: counts.len(),
+            });
+        }
+
+        Ukeire::of_counts(&counts, &seen(&counts, visible)?)
+    }
+
+    /// How many tiles are accepted in total
+    pub fn total(&self) -> usize {
+        self.tiles.iter().map(|tile| tile.remaining as usize).sum()
+    }
+
+    fn of_counts(counts: &TileCounts, seen: &[u8; TILE_KINDS]) -> Result<Ukeire, DecodeErr> {
+        let shanten = Shanten::of_counts(counts)?.min();
+        let mut tiles = vec![];
+
+        for (index, seen) in seen.iter().enumerate() {
+            let tile = Suit::from_index(index).unwrap();
+            let mut drawn = *counts;
+
+            if drawn.add(tile) && Shanten::of_counts(&drawn)?.min() < shanten {
+                tiles.push(Acceptance {
+                    tile,
+                    remaining: 4u8.saturating_sub(*seen),
+                });
+            }
+        }
+
+        Ok(Ukeire { shanten, tiles })
+    }
+}
+
+/// A tile that could be discarded from a hand, and what the hand would accept after
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Discard {
+    /// The tile to discard
+    pub tile: Suit,
+    /// What the hand would accept after discarding it
+    pub ukeire: Ukeire,
+}
+
+impl Discard {
+    /// Ranks every possible discard from a hand that has just drawn, i.e. one of 14,
+    /// 11, 8... tiles. The best discard comes first: the lowest shanten, then the
+    /// most tiles accepted. Red fives are ranked separately from plain fives.
+    /// Can throw a [DecodeErr]
+    ///
+    /// ```
+    /// # use mahjong_encoding::*;
+    /// let hand = Suit::from_mpsz("123m456p789s23m177z").unwrap();
+    /// let discards = Discard::rank(&hand, &[]).unwrap();
+    ///
+    /// assert_eq!(discards[0].tile, Suit::Wind(Wind::East));
+    /// assert_eq!(discards[0].ukeire.shanten, 0);
+    /// ```
+    pub fn rank(hand: &[Suit], visible: &[Suit]) -> Result<Vec<Discard>, DecodeErr> {
+        let counts = TileCounts::from_tiles(hand)?;
+        if counts.len() % 3 != 2 {
+            return Err(DecodeErr::InvalidHandLength {
+                length: counts.len(),
+            });
+        }
+
+        let seen = seen(&counts, visible)?;
+        let mut tiles = hand.to_vec();
+        Suit::sort_hand(&mut tiles);
+        tiles.dedup();
+
+        let mut discards = vec![];
+        for tile in tiles {
+            let mut rest = counts;
+            rest.remove(tile);
+
+            discards.push(Discard {
+                tile,
+                ukeire: Ukeire::of_counts(&rest, &seen)?,
+            });
+        }
+
+        discards.sort_by_key(|discard| {
+            (
+                discard.ukeire.shanten,
+                std::cmp::Reverse(discard.ukeire.total()),
+            )
+        });
+
+        Ok(discards)
+    }
+}
+
+/// How many copies of each tile are accounted for, by the hand or on the table
+fn seen(counts: &TileCounts, visible: &[Suit]) -> Result<[u8; TILE_KINDS], DecodeErr> {
+    let mut seen = *counts.counts();
+
+    for tile in visible {
+        let index = tile.index().ok_or(DecodeErr::InvalidTile { tile: *tile })?;
+        seen[index] = seen[index].saturating_add(1);
+    }
+
+    Ok(seen)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn tiles(hand: &str) -> Vec<Suit> {
+        Suit::from_mpsz(hand).unwrap()
+    }
+
+    #[test]
+    fn lists_waits_of_a_tenpai_hand() {
+        let ukeire = Ukeire::of(&tiles("123m456p789s23m11z"), &[]).unwrap();
+
+        assert_eq!(
+            ukeire.tiles,
+            [
+                Acceptance {
+                    tile: Suit::Characters(1),
+                    remaining: 3,
+                },
+                Acceptance {
+                    tile: Suit::Characters(4),
+                    remaining: 4,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn takes_visible_tiles_out_of_the_count() {
+        let ukeire = Ukeire::of(&tiles("123m456p789s23m11z"), &tiles("14444m")).unwrap();
+
+        assert_eq!(ukeire.tiles[0].remaining, 2);
+        assert_eq!(ukeire.tiles[1].remaining, 0);
+        assert_eq!(ukeire.total(), 2);
+    }
+
+    #[test]
+    fn lists_improving_tiles_of_an_iishanten_hand() {
+        let ukeire = Ukeire::of(&tiles("123m456p789s24m17z"), &[]).unwrap();
+
+        assert_eq!(ukeire.shanten, 1);
+        assert!(ukeire
+            .tiles
+            .iter()
+            .any(|tile| tile.tile == Suit::Characters(3)));
+        assert!(ukeire.tiles.iter().all(|tile| tile.tile != Suit::Bamboo(1)));
+    }
+
+    #[test]
+    fn ranks_discards_by_acceptance() {
+        let discards = Discard::rank(&tiles("123m456p789s2399m1z"), &[]).unwrap();
+
+        assert_eq!(discards[0].tile, Suit::Wind(Wind::East));
+        assert_eq!(discards[0].ukeire.shanten, 0);
+        assert_eq!(discards[0].ukeire.total(), 7);
+        assert!(discards
+            .windows(2)
+            .all(|pair| pair[0].ukeire.shanten <= pair[1].ukeire.shanten));
+    }
+
+    #[test]
+    fn rejects_hands_of_the_wrong_length() {
+        assert_eq!(
+            Ukeire::of(&tiles("123m456p789s23m111z"), &[]),
+            Err(DecodeErr::InvalidHandLength { length: 14 })
+        );
+        assert_eq!(
+            Discard::rank(&tiles("123m456p789s23m11z"), &[]),
+            Err(DecodeErr::InvalidHandLength { length: 13 })
+        );
+    }
+}
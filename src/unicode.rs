@@ -0,0 +1,199 @@
+//! The Mahjong Tiles block of Unicode, `U+1F000`–`U+1F02B`, e.g. 🀇🀈🀉.
+//!
+//! Unicode has no red fives, so a red five is written as its plain five followed by
+//! [RED_MARKER].
+
+use crate::*;
+
+/// Follows a five to mark it as a [RED_FIVE]
+pub const RED_MARKER: char = '*';
+
+/// Variation selector that some chat clients add after 🀄 to draw it as an emoji
+const EMOJI_PRESENTATION: char = '\u{FE0F}';
+
+const EXPECTED: &str = "a mahjong tile between U+1F000 and U+1F021, or whitespace";
+const EXPECTED_FIVE: &str = "a red marker only after a five";
+
+const WINDS: [Wind; 4] = [Wind::East, Wind::South, Wind::West, Wind::North];
+/// 🀄 is the red dragon, so dragons run the opposite way to [Dragon]'s order
+const DRAGONS: [Dragon; 3] = [Dragon::Red, Dragon::Green, Dragon::White];
+const FLOWERS: [Flower; 8] = [
+    Flower::Plum,
+    Flower::Orchid,
+    Flower::Bamboo,
+    Flower::Chrysanthemum,
+    Flower::Spring,
+    Flower::Summer,
+    Flower::Autumn,
+    Flower::Winter,
+];
+
+impl Suit {
+    /// The Unicode glyph for a tile. A red five has the same glyph as a plain five,
+    /// tiles that aren't [valid](Suit::is_valid) have none
+    ///
+    /// ```
+    /// # use mahjong_encoding::*;
+    /// assert_eq!(Suit::Characters(1).to_char(), Some('🀇'));
+    /// assert_eq!(Suit::Dragon(Dragon::Red).to_char(), Some('🀄'));
+    /// assert_eq!(Suit::Dots(0).to_char(), None);
+    /// ```
+    pub fn to_char(&self) -> Option<char> {
+        if !self.is_valid() {
+            return None;
+        }
+
+        let offset = match self.normalize() {
+            Suit::Wind(wind) => WINDS.iter().position(|other| *other == wind)?,
+            Suit::Dragon(dragon) => 4 + DRAGONS.iter().position(|other| *other == dragon)?,
+            Suit::Characters(n) => 6 + n as usize,
+            Suit::Bamboo(n) => 15 + n as usize,
+            Suit::Dots(n) => 24 + n as usize,
+        };
+
+        char::from_u32(0x1F000 + offset as u32)
+    }
+
+    /// The tile for a Unicode glyph, never a red five
+    ///
+    /// ```
+    /// # use mahjong_encoding::*;
+    /// assert_eq!(Suit::from_char('🀙'), Some(Suit::Dots(1)));
+    /// assert_eq!(Suit::from_char('m'), None);
+    /// ```
+    pub fn from_char(glyph: char) -> Option<Suit> {
+        let offset = (glyph as u32).checked_sub(0x1F000)? as usize;
+
+        Some(match offset {
+            0..=3 => Suit::Wind(WINDS[offset]),
+            4..=6 => Suit::Dragon(DRAGONS[offset - 4]),
+            7..=15 => Suit::Characters(offset as u8 - 6),
+            16..=24 => Suit::Bamboo(offset as u8 - 15),
+            25..=33 => Suit::Dots(offset as u8 - 24),
+            _ => return None,
+        })
+    }
+
+    /// Converts an array or vec of [Suit] into Unicode tiles, red fives are followed
+    /// by [RED_MARKER] and tiles without a glyph are written as `?`
+    ///
+    /// ```
+    /// # use mahjong_encoding::*;
+    /// let hand = Suit::from_mpsz("123m0p7z").unwrap();
+    /// assert_eq!(Suit::to_unicode(&hand), "🀇🀈🀉🀝*🀄");
+    /// ```
+    pub fn to_unicode(hand: &[Suit]) -> String {
+        let mut output = String::new();
+
+        for tile in hand {
+            output.push(tile.to_char().unwrap_or('?'));
+            if tile.is_red() {
+                output.push(RED_MARKER);
+            }
+        }
+
+        output
+    }
+
+    /// Converts from Unicode tiles into a hand. Whitespace and the emoji variation
+    /// selector are skipped. Can throw a [DecodeErr] pointing at the byte offset of
+    /// the offending character
+    ///
+    /// ```
+    /// # use mahjong_encoding::*;
+    /// let hand = Suit::from_unicode("🀐🀑🀒 🀔* 🀄\u{FE0F}").unwrap();
+    /// assert_eq!(Suit::to_mpsz(&hand), "1230s7z");
+    /// ```
+    pub fn from_unicode(input: &str) -> Result<Vec<Suit>, DecodeErr> {
+        let mut hand: Vec<Suit> = vec![];
+
+        for (position, glyph) in input.char_indices() {
+            let err = |expected| DecodeErr::InvalidCharacter {
+                byte: input.as_bytes()[position],
+                position,
+                expected,
+            };
+
+            if glyph == RED_MARKER {
+                let five = hand
+                    .last_mut()
+                    .filter(|tile| tile.number() == Some(5) && !tile.is_red())
+                    .ok_or(err(EXPECTED_FIVE))?;
+                *five = five.to_red();
+            } else if glyph != EMOJI_PRESENTATION && !glyph.is_whitespace() {
+                hand.push(Suit::from_char(glyph).ok_or(err(EXPECTED))?);
+            }
+        }
+
+        Ok(hand)
+    }
+}
+
+impl Flower {
+    /// The Unicode glyph for a flower
+    ///
+    /// ```
+    /// # use mahjong_encoding::*;
+    /// assert_eq!(Flower::Plum.to_char(), '🀢');
+    /// ```
+    pub fn to_char(&self) -> char {
+        let offset = FLOWERS.iter().position(|other| other == self).unwrap();
+        char::from_u32(0x1F022 + offset as u32).unwrap()
+    }
+
+    /// The flower for a Unicode glyph
+    pub fn from_char(glyph: char) -> Option<Flower> {
+        let offset = (glyph as u32).checked_sub(0x1F022)?;
+        FLOWERS.get(offset as usize).copied()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn round_trips_every_tile() {
+        for tile in (0..TILE_KINDS).filter_map(Suit::from_index) {
+            assert_eq!(Suit::from_char(tile.to_char().unwrap()), Some(tile));
+        }
+        for flower in FLOWERS {
+            assert_eq!(Flower::from_char(flower.to_char()), Some(flower));
+        }
+
+        let hand = Suit::from_mpsz("1230m406p555s1234567z").unwrap();
+        assert_eq!(Suit::from_unicode(&Suit::to_unicode(&hand)), Ok(hand));
+    }
+
+    #[test]
+    fn matches_the_unicode_names() {
+        assert_eq!(Suit::Wind(Wind::East).to_char(), Some('\u{1F000}'));
+        assert_eq!(Suit::Dragon(Dragon::White).to_char(), Some('\u{1F006}'));
+        assert_eq!(Suit::Characters(9).to_char(), Some('\u{1F00F}'));
+        assert_eq!(Suit::Bamboo(1).to_char(), Some('\u{1F010}'));
+        assert_eq!(Suit::Dots(9).to_char(), Some('\u{1F021}'));
+        assert_eq!(Flower::Winter.to_char(), '\u{1F029}');
+        assert_eq!(Suit::from_char('\u{1F02B}'), None);
+    }
+
+    #[test]
+    fn reports_bad_characters() {
+        assert_eq!(
+            Suit::from_unicode("🀇x"),
+            Err(DecodeErr::InvalidCharacter {
+                byte: b'x',
+                position: 4,
+                expected: EXPECTED,
+            })
+        );
+        assert_eq!(
+            Suit::from_unicode("🀇*"),
+            Err(DecodeErr::InvalidCharacter {
+                byte: b'*',
+                position: 4,
+                expected: EXPECTED_FIVE,
+            })
+        );
+        assert!(Suit::from_unicode("🀋**").is_err());
+    }
+}
@@ -0,0 +1,550 @@
+//! 山 _(yama)_, the wall that every tile of a round is drawn from.
+//!
+//! The tiles are shuffled by a small generator built into the crate and seeded from a
+//! single `u64`, so the same seed and [WallRules] give the same wall on any platform.
+//! A wall can be shared in the text format as a [Payload::Game].
+
+use crate::*;
+
+/// Tiles in the 王牌 _(wanpai)_, the dead wall
+const DEAD_WALL: usize = 14;
+/// Replacement tiles at the far end of the dead wall, one for each kan
+const REPLACEMENTS: usize = 4;
+/// The most ドラ表示牌 _(dora hyoujihai)_ that can be revealed
+const MAX_INDICATORS: usize = 5;
+/// Characters the seed takes up in the text format, six bits each
+const SEED_LENGTH: usize = 11;
+
+const EXPECTED_FLAGS: &str = "wall flags, A or B";
+const EXPECTED_END: &str = "the end of the input";
+
+const FLOWERS: [Flower; 8] = [
+    Flower::Plum,
+    Flower::Orchid,
+    Flower::Chrysanthemum,
+    Flower::Bamboo,
+    Flower::Spring,
+    Flower::Summer,
+    Flower::Autumn,
+    Flower::Winter,
+];
+
+/// What goes into a [Wall]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct WallRules {
+    /// How many of the four fives of 萬子 _(manzu)_, 餅子 _(pinzu)_ and 索子 _(so-zu)_
+    /// are [RED_FIVE]s
+    pub red_fives: [u8; 3],
+    /// Whether the eight [Flower]s are shuffled in as well
+    pub flowers: bool,
+}
+
+/// One red five in each suit and no flowers
+impl Default for WallRules {
+    fn default() -> WallRules {
+        WallRules {
+            red_fives: [1, 1, 1],
+            flowers: false,
+        }
+    }
+}
+
+/// A single tile of the [Wall], which may be a [Flower]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum WallTile {
+    /// A tile that goes into the hand
+    Tile(Suit),
+    /// A flower, set aside as soon as it is drawn
+    Flower(Flower),
+}
+
+impl WallTile {
+    /// The tile, `None` for a flower
+    pub fn tile(&self) -> Option<Suit> {
+        match self {
+            WallTile::Tile(tile) => Some(*tile),
+            WallTile::Flower(_) => None,
+        }
+    }
+}
+
+/// The starting hands dealt by [Wall::deal], in seat order from the dealer
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Deal {
+    /// 配牌 _(haipai)_, each player's 13 tiles
+    pub hands: [Vec<Suit>; 4],
+    /// The flowers each player was dealt, already replaced from the dead wall
+    pub flowers: [Vec<Flower>; 4],
+}
+
+/// A shuffled wall, already broken at the dice roll. The 14 tiles before the break
+/// are the 王牌 _(wanpai)_, the dead wall that holds the ドラ表示牌
+/// _(dora hyoujihai)_ and the replacement tiles for kans and flowers. Every other
+/// tile is drawn in order, starting from the break.
+///
+/// ```
+/// # use mahjong_encoding::*;
+/// let mut wall = Wall::new(2024, WallRules::default()).unwrap();
+/// let deal = wall.deal();
+///
+/// assert!(deal.hands.iter().all(|hand| hand.len() == 13));
+/// assert_eq!(wall.remaining(), 70);
+/// assert_eq!(wall.dora_indicators().len(), 1);
+///
+/// // the same seed deals the same hands, even after a trip through the text format
+/// let text = Payload::Game(wall).to_string();
+/// let Ok(Payload::Game(mut shared)) = Payload::from_string(&text) else {
+///     panic!("expected a wall");
+/// };
+/// assert_eq!(shared.deal(), deal);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Wall {
+    seed: u64,
+    rules: WallRules,
+    dice: [u8; 2],
+    live: Vec<WallTile>,
+    drawn: usize,
+    dead: Vec<WallTile>,
+    replaced: usize,
+    revealed: usize,
+}
+
+impl Wall {
+    /// Builds, shuffles and breaks a wall. Can throw a [DecodeErr] if more than four
+    /// fives of a suit are asked to be red
+    pub fn new(seed: u64, rules: WallRules) -> Result<Wall, DecodeErr> {
+        let mut tiles = vec![];
+        for index in 0..TILE_KINDS {
+            let tile = Suit::from_index(index).unwrap();
+            tiles.extend([WallTile::Tile(tile); 4]);
+        }
+
+        for (suit, count) in rules.red_fives.iter().enumerate() {
+            let five = Suit::from_index(suit * 9 + 4).unwrap();
+            if *count > 4 {
+                return Err(DecodeErr::TooManyCopies {
+                    tile: five.to_red(),
+                    count: *count as usize,
+                });
+            }
+
+            let fives = tiles
+                .iter_mut()
+                .filter(|tile| **tile == WallTile::Tile(five))
+                .take(*count as usize);
+            for tile in fives {
+                *tile = WallTile::Tile(five.to_red());
+            }
+        }
+        if rules.flowers {
+            tiles.extend(FLOWERS.map(WallTile::Flower));
+        }
+
+        let mut rng = Rng::new(seed);
+        for i in (1..tiles.len()).rev() {
+            tiles.swap(i, rng.below(i as u64 + 1) as usize);
+        }
+        let dice = [rng.below(6) as u8 + 1, rng.below(6) as u8 + 1];
+
+        // The sides of the table in the order tiles are drawn, starting with the
+        // dealer's and moving to their left. The dice count sides the other way.
+        let length = tiles.len();
+        let stacks = length / 2;
+        let side = [0, 3, 2, 1][(dice[0] + dice[1] - 1) as usize % 4];
+        let break_at = (side * stacks / 4 + (dice[0] + dice[1]) as usize) * 2;
+        tiles.rotate_left((break_at + length - DEAD_WALL) % length);
+
+        let live = tiles.split_off(DEAD_WALL);
+        Ok(Wall {
+            seed,
+            rules,
+            dice,
+            live,
+            drawn: 0,
+            dead: tiles,
+            replaced: 0,
+            revealed: 1,
+        })
+    }
+
+    /// The seed the wall was shuffled with
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// What went into the wall
+    pub fn rules(&self) -> WallRules {
+        self.rules
+    }
+
+    /// The dice that were rolled to break the wall
+    pub fn dice(&self) -> [u8; 2] {
+        self.dice
+    }
+
+    /// How many tiles are left to draw, not counting the dead wall
+    pub fn remaining(&self) -> usize {
+        self.live.len() - self.drawn
+    }
+
+    /// ツモ _(tsumo)_, draws the next tile. `None` once the wall is exhausted
+    pub fn draw(&mut self) -> Option<WallTile> {
+        let tile = *self.live.get(self.drawn)?;
+        self.drawn += 1;
+        Some(tile)
+    }
+
+    /// 嶺上牌 _(rinshanhai)_, draws a replacement tile from the dead wall after a kan
+    /// or a flower. The last tile of the live wall moves into the dead wall to keep it
+    /// at 14 tiles. `None` once the wall is exhausted
+    pub fn draw_replacement(&mut self) -> Option<WallTile> {
+        if self.remaining() == 0 {
+            return None;
+        }
+
+        let index = match self.replaced {
+            n if n < REPLACEMENTS => n,
+            n => DEAD_WALL + n - REPLACEMENTS,
+        };
+        self.replaced += 1;
+        self.dead.push(self.live.pop()?);
+        Some(self.dead[index])
+    }
+
+    /// Deals 13 tiles to each player, four at a time and then one each, starting
+    /// with the dealer. Any flowers dealt are set aside and replaced, dealer first.
+    pub fn deal(&mut self) -> Deal {
+        let mut dealt: [Vec<WallTile>; 4] = Default::default();
+        for round in 0..4 {
+            for hand in dealt.iter_mut() {
+                let count = if round < 3 { 4 } else { 1 };
+                hand.extend((0..count).filter_map(|_| self.draw()));
+            }
+        }
+
+        let mut deal = Deal {
+            hands: Default::default(),
+            flowers: Default::default(),
+        };
+        for (seat, hand) in dealt.iter().enumerate() {
+            let mut pending = hand.clone();
+            while let Some(tile) = pending.pop() {
+                match tile {
+                    WallTile::Tile(tile) => deal.hands[seat].push(tile),
+                    WallTile::Flower(flower) => {
+                        deal.flowers[seat].push(flower);
+                        pending.extend(self.draw_replacement());
+                    }
+                }
+            }
+
+            Suit::sort_hand(&mut deal.hands[seat]);
+            deal.flowers[seat].sort();
+        }
+
+        deal
+    }
+
+    /// 槓ドラ _(kan dora)_, reveals the next ドラ表示牌 _(dora hyoujihai)_ after a kan.
+    /// `None` once all five are showing
+    pub fn reveal_dora(&mut self) -> Option<Suit> {
+        if self.revealed == MAX_INDICATORS {
+            return None;
+        }
+
+        self.revealed += 1;
+        self.dora_indicators().last().copied()
+    }
+
+    /// ドラ表示牌 _(dora hyoujihai)_ revealed so far. A flower is passed over for the
+    /// tile in the same place of the next stack
+    pub fn dora_indicators(&self) -> Vec<Suit> {
+        self.indicators(0)
+    }
+
+    /// 裏ドラ表示牌 _(ura dora hyoujihai)_, the tiles under each revealed indicator
+    pub fn ura_dora_indicators(&self) -> Vec<Suit> {
+        self.indicators(1)
+    }
+
+    /// Reads the indicators from the third stack of the dead wall onwards, `layer` `0`
+    /// is the top of each stack and `1` the bottom
+    fn indicators(&self, layer: usize) -> Vec<Suit> {
+        (0..self.revealed)
+            .filter_map(|n| {
+                let start = REPLACEMENTS + n * 2 + layer;
+                self.dead[start..]
+                    .iter()
+                    .step_by(2)
+                    .find_map(WallTile::tile)
+            })
+            .collect()
+    }
+
+    /// Writes the seed and rules in the text format, without a [Header]. Reading it
+    /// back gives the wall as it was before anything was drawn
+    pub(crate) fn encode(&self) -> String {
+        let red = self.rules.red_fives;
+        let mut values = vec![self.rules.flowers as u8, red[0], red[1], red[2]];
+        values.extend(
+            (0..SEED_LENGTH)
+                .rev()
+                .map(|i| (self.seed >> (i * 6)) as u8 & 0x3F),
+        );
+
+        values
+            .iter()
+            .map(|value| ALPHABET[*value as usize] as char)
+            .collect()
+    }
+
+    /// Reads a wall written by [Wall::encode]
+    pub(crate) fn decode(input: &str) -> Result<Wall, DecodeErr> {
+        let mut values = vec![];
+        for (position, byte) in input.bytes().enumerate() {
+            if position == 4 + SEED_LENGTH {
+                return Err(DecodeErr::InvalidCharacter {
+                    byte,
+                    position,
+                    expected: EXPECTED_END,
+                });
+            }
+
+            values.push(value_of(byte).ok_or(DecodeErr::InvalidCharacter {
+                byte,
+                position,
+                expected: EXPECTED_ALPHABET,
+            })?);
+        }
+        if values.len() < 4 + SEED_LENGTH {
+            return Err(DecodeErr::UnexpectedEnd {
+                position: input.len(),
+            });
+        }
+
+        let flowers = match values[0] {
+            0 | 1 => values[0] == 1,
+            _ => {
+                return Err(DecodeErr::InvalidCharacter {
+                    byte: input.as_bytes()[0],
+                    position: 0,
+                    expected: EXPECTED_FLAGS,
+                })
+            }
+        };
+        // 66 bits are written, the top two must be clear
+        if values[4] > 0xF {
+            return Err(DecodeErr::InvalidPacking);
+        }
+        let seed = values[4..]
+            .iter()
+            .fold(0, |seed, value| seed << 6 | *value as u64);
+
+        Wall::new(
+            seed,
+            WallRules {
+                red_fives: [values[1], values[2], values[3]],
+                flowers,
+            },
+        )
+    }
+}
+
+/// xoshiro256** seeded through SplitMix64, small and fast with no bias worth
+/// worrying about for shuffling tiles
+#[derive(Debug, Clone)]
+struct Rng {
+    state: [u64; 4],
+}
+
+impl Rng {
+    fn new(seed: u64) -> Rng {
+        let mut seed = seed;
+        let mut split_mix = || {
+            seed = seed.wrapping_add(0x9E3779B97F4A7C15);
+            let mut z = seed;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+            z ^ (z >> 31)
+        };
+
+        Rng {
+            state: [split_mix(), split_mix(), split_mix(), split_mix()],
+        }
+    }
+
+    fn next(&mut self) -> u64 {
+        let s = &mut self.state;
+        let result = s[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = s[1] << 17;
+
+        s[2] ^= s[0];
+        s[3] ^= s[1];
+        s[1] ^= s[2];
+        s[0] ^= s[3];
+        s[2] ^= t;
+        s[3] = s[3].rotate_left(45);
+
+        result
+    }
+
+    /// A uniform number in `0..bound`, rejecting the values that would favour the
+    /// low numbers
+    fn below(&mut self, bound: u64) -> u64 {
+        let limit = u64::MAX - u64::MAX % bound;
+        loop {
+            let value = self.next();
+            if value < limit {
+                return value % bound;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn all_tiles(wall: &Wall) -> Vec<WallTile> {
+        wall.live.iter().chain(&wall.dead).copied().collect()
+    }
+
+    #[test]
+    fn generates_the_reference_sequence() {
+        // the first outputs of xoshiro256** seeded through SplitMix64 from 0
+        let mut rng = Rng::new(0);
+        assert_eq!(rng.state[0], 0xE220A8397B1DCDAF);
+        assert_eq!(rng.next(), 0x99EC5F36CB75F2B4);
+    }
+
+    #[test]
+    fn holds_every_tile() {
+        let wall = Wall::new(7, WallRules::default()).unwrap();
+        let tiles = all_tiles(&wall)
+            .iter()
+            .filter_map(WallTile::tile)
+            .collect::<Vec<_>>();
+        let counts = TileCounts::from_tiles(&tiles).unwrap();
+
+        assert_eq!(tiles.len(), 136);
+        assert!(counts.counts().iter().all(|count| *count == 4));
+        assert_eq!(counts.red_fives(), [1, 1, 1]);
+        assert_eq!(wall.dead.len(), DEAD_WALL);
+
+        let rules = WallRules {
+            red_fives: [0, 2, 4],
+            flowers: true,
+        };
+        let wall = Wall::new(7, rules).unwrap();
+        let tiles = all_tiles(&wall);
+        let red = tiles.iter().filter_map(WallTile::tile).collect::<Vec<_>>();
+
+        assert_eq!(tiles.len(), 144);
+        assert_eq!(TileCounts::from_tiles(&red).unwrap().red_fives(), [0, 2, 4]);
+        assert!(Wall::new(
+            7,
+            WallRules {
+                red_fives: [5, 0, 0],
+                flowers: false
+            }
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn shuffles_by_seed() {
+        let first = Wall::new(1, WallRules::default()).unwrap();
+
+        assert_eq!(Wall::new(1, WallRules::default()).unwrap(), first);
+        assert_ne!(
+            all_tiles(&Wall::new(2, WallRules::default()).unwrap()),
+            all_tiles(&first)
+        );
+        assert!((1..=6).contains(&first.dice()[0]));
+    }
+
+    #[test]
+    fn draws_to_the_end() {
+        let mut wall = Wall::new(3, WallRules::default()).unwrap();
+        let deal = wall.deal();
+
+        assert_eq!(deal.flowers, <[Vec<Flower>; 4]>::default());
+        assert_eq!(wall.remaining(), 136 - DEAD_WALL - 52);
+
+        let replacement = wall.draw_replacement().unwrap();
+        assert_eq!(replacement, wall.dead[0]);
+        assert_eq!(wall.remaining(), 69);
+        assert_eq!(wall.dead.len(), DEAD_WALL + 1);
+
+        let mut drawn = 0;
+        while wall.draw().is_some() {
+            drawn += 1;
+        }
+        assert_eq!(drawn, 69);
+        assert_eq!(wall.draw_replacement(), None);
+    }
+
+    #[test]
+    fn reveals_up_to_five_indicators() {
+        let mut wall = Wall::new(4, WallRules::default()).unwrap();
+
+        assert_eq!(wall.dora_indicators(), [wall.dead[4].tile().unwrap()]);
+        assert_eq!(wall.ura_dora_indicators(), [wall.dead[5].tile().unwrap()]);
+        for n in 1..MAX_INDICATORS {
+            assert_eq!(wall.reveal_dora(), wall.dead[4 + n * 2].tile());
+        }
+        assert_eq!(wall.reveal_dora(), None);
+        assert_eq!(wall.dora_indicators().len(), 5);
+    }
+
+    #[test]
+    fn replaces_dealt_flowers() {
+        let rules = WallRules {
+            red_fives: [1, 1, 1],
+            flowers: true,
+        };
+
+        for seed in 0..20 {
+            let mut wall = Wall::new(seed, rules).unwrap();
+            let deal = wall.deal();
+            let flowers = deal.flowers.iter().map(Vec::len).sum::<usize>();
+
+            assert!(deal.hands.iter().all(|hand| hand.len() == 13));
+            assert_eq!(wall.remaining(), 144 - DEAD_WALL - 52 - flowers);
+        }
+    }
+
+    #[test]
+    fn round_trips_through_text() {
+        let rules = WallRules {
+            red_fives: [0, 3, 1],
+            flowers: true,
+        };
+        let wall = Wall::new(u64::MAX, rules).unwrap();
+        let text = wall.encode();
+
+        assert_eq!(text.len(), 4 + SEED_LENGTH);
+        assert_eq!(Wall::decode(&text), Ok(wall));
+        assert_eq!(
+            Wall::decode(&text[..5]),
+            Err(DecodeErr::UnexpectedEnd { position: 5 })
+        );
+        assert_eq!(
+            Wall::decode(&format!("C{}", &text[1..])),
+            Err(DecodeErr::InvalidCharacter {
+                byte: b'C',
+                position: 0,
+                expected: EXPECTED_FLAGS,
+            })
+        );
+        assert_eq!(
+            Wall::decode(&format!("{text}A")),
+            Err(DecodeErr::InvalidCharacter {
+                byte: b'A',
+                position: 15,
+                expected: EXPECTED_END,
+            })
+        );
+    }
+}
@@ -0,0 +1,207 @@
+//! Just enough XML to read flat logs made of empty elements with attributes, such as
+//! Tenhou's mjlog. Text content, comments and processing instructions are skipped.
+
+use crate::*;
+
+const EXPECTED_NAME: &str = "an element name";
+const EXPECTED_ATTRIBUTE: &str = "an attribute, > or />";
+const EXPECTED_QUOTE: &str = "a quoted attribute value";
+
+/// An opening or empty element, closing tags are skipped
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Element<'a> {
+    pub(crate) name: &'a str,
+    pub(crate) attributes: Vec<(&'a str, String)>,
+}
+
+impl Element<'_> {
+    /// The value of an attribute, if it is there
+    pub(crate) fn get(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Every opening or empty element in `input`, in document order
+pub(crate) fn elements(input: &str) -> Result<Vec<Element<'_>>, DecodeErr> {
+    let bytes = input.as_bytes();
+    let mut position = 0;
+    let mut elements = vec![];
+
+    let skip_past = |position: usize, end: &str| {
+        input[position..]
+            .find(end)
+            .map(|found| position + found + end.len())
+            .ok_or(DecodeErr::UnexpectedEnd {
+                position: input.len(),
+            })
+    };
+
+    while let Some(found) = input[position..].find('<') {
+        position += found;
+        let rest = &input[position..];
+
+        if rest.starts_with("<?") {
+            position = skip_past(position, "?>")?;
+            continue;
+        }
+        if rest.starts_with("<!--") {
+            position = skip_past(position, "-->")?;
+            continue;
+        }
+        if rest.starts_with("</") || rest.starts_with("<!") {
+            position = skip_past(position, ">")?;
+            continue;
+        }
+
+        position += 1;
+        let name = word(input, &mut position);
+        if name.is_empty() {
+            return Err(invalid(bytes, position, EXPECTED_NAME));
+        }
+
+        let mut attributes = vec![];
+        loop {
+            while bytes.get(position).is_some_and(u8::is_ascii_whitespace) {
+                position += 1;
+            }
+
+            match bytes.get(position) {
+                None => {
+                    return Err(DecodeErr::UnexpectedEnd { position });
+                }
+                Some(b'>') => {
+                    position += 1;
+                    break;
+                }
+                Some(b'/') if bytes.get(position + 1) == Some(&b'>') => {
+                    position += 2;
+                    break;
+                }
+                _ => {}
+            }
+
+            let key = word(input, &mut position);
+            if key.is_empty() || bytes.get(position) != Some(&b'=') {
+                return Err(invalid(bytes, position, EXPECTED_ATTRIBUTE));
+            }
+            position += 1;
+
+            let quote = match bytes.get(position) {
+                Some(quote @ (b'"' | b'\'')) => *quote as char,
+                _ => return Err(invalid(bytes, position, EXPECTED_QUOTE)),
+            };
+            let end = skip_past(position + 1, &quote.to_string())?;
+            attributes.push((key, unescape(&input[position + 1..end - 1])));
+            position = end;
+        }
+
+        elements.push(Element { name, attributes });
+    }
+
+    Ok(elements)
+}
+
+/// Reads a name made of anything but whitespace, `=`, `/` and `>`
+fn word<'a>(input: &'a str, position: &mut usize) -> &'a str {
+    let start = *position;
+    let length = input[start..]
+        .find(|c: char| c.is_ascii_whitespace() || matches!(c, '=' | '/' | '>'))
+        .unwrap_or(input.len() - start);
+
+    *position += length;
+    &input[start..start + length]
+}
+
+fn invalid(bytes: &[u8], position: usize, expected: &'static str) -> DecodeErr {
+    match bytes.get(position) {
+        Some(&byte) => DecodeErr::InvalidCharacter {
+            byte,
+            position,
+            expected,
+        },
+        None => DecodeErr::UnexpectedEnd { position },
+    }
+}
+
+/// Replaces the predefined entities and numeric character references
+fn unescape(value: &str) -> String {
+    let mut output = String::new();
+    let mut rest = value;
+
+    while let Some(start) = rest.find('&') {
+        output.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let Some(end) = rest.find(';') else {
+            break;
+        };
+        let entity = &rest[1..end];
+        let replacement = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(str::parse))
+                .and_then(Result::ok)
+                .and_then(char::from_u32),
+        };
+
+        match replacement {
+            Some(c) => {
+                output.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                output.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    output.push_str(rest);
+    output
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn reads_elements_and_attributes() {
+        let input = r#"<?xml version="1.0"?><log a="1"><!-- skipped --><T12/>text<X b='&lt;&#65;&amp;' c="" /></log>"#;
+        let found = elements(input).unwrap();
+
+        assert_eq!(
+            found.iter().map(|element| element.name).collect::<Vec<_>>(),
+            ["log", "T12", "X"]
+        );
+        assert_eq!(found[0].get("a"), Some("1"));
+        assert_eq!(found[2].get("b"), Some("<A&"));
+        assert_eq!(found[2].get("c"), Some(""));
+        assert_eq!(found[2].get("d"), None);
+    }
+
+    #[test]
+    fn reports_malformed_input() {
+        assert_eq!(
+            elements("<T1 a=1/>"),
+            Err(DecodeErr::InvalidCharacter {
+                byte: b'1',
+                position: 6,
+                expected: EXPECTED_QUOTE,
+            })
+        );
+        assert_eq!(
+            elements(r#"<T1 a="1"#),
+            Err(DecodeErr::UnexpectedEnd { position: 8 })
+        );
+        assert!(elements("< >").is_err());
+    }
+}
@@ -0,0 +1,783 @@
+use crate::*;
+
+/// How a hand was won
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum WinType {
+    /// ツモ _(tsumo)_, won on a self drawn tile
+    Tsumo,
+    /// ロン _(ron)_, won on another player's discard
+    Ron,
+}
+
+/// 立直 _(riichi)_ status of the winning player
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum Riichi {
+    /// No riichi was declared
+    #[default]
+    None,
+    /// 立直 _(riichi)_
+    Riichi,
+    /// ダブル立直 _(daburu riichi)_, declared on the first discard
+    DoubleRiichi,
+}
+
+/// Everything needed to work out the 役 _(yaku)_ of a winning hand
+///
+/// ```rust
+/// # use mahjong_encoding::*;
+/// let hand = Suit::from_mpsz("234m456p678s22345s").unwrap();
+/// let mut win = Win::new(hand, vec![], Suit::Bamboo(2), WinType::Tsumo, Wind::East, Wind::East);
+/// win.riichi = Riichi::Riichi;
+///
+/// let best = win.yaku().unwrap().unwrap();
+/// assert!(best.yaku.contains(&Yaku::Tanyao));
+/// assert_eq!(best.han(), 3);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Win {
+    /// The concealed tiles, including the winning tile
+    pub concealed: Vec<Suit>,
+    /// Called melds and closed kans
+    pub melds: Vec<Meld>,
+    /// The tile the hand was won on
+    pub winning_tile: Suit,
+    /// Whether the winning tile was drawn or claimed
+    pub win_type: WinType,
+    /// 自風 _(jikaze)_, the winner's seat wind
+    pub seat_wind: Wind,
+    /// 場風 _(bakaze)_, the wind of the round
+    pub round_wind: Wind,
+    /// Riichi status of the winner
+    pub riichi: Riichi,
+    /// 一発 _(ippatsu)_, won within a turn of declaring riichi
+    pub ippatsu: bool,
+    /// Won on the last tile of the wall, 海底 _(haitei)_ or 河底 _(houtei)_
+    pub last_tile: bool,
+    /// 嶺上開花 _(rinshan kaihou)_, won on the replacement tile after a kan
+    pub after_kan: bool,
+    /// 搶槓 _(chankan)_, won on a tile added to a kan
+    pub robbed_kan: bool,
+    /// Won on the very first draw of the hand, without any calls
+    pub first_draw: bool,
+    /// How many ドラ _(dora)_ the hand holds, not counting red fives, see
+    /// [Suit::count_dora]
+    pub dora: u8,
+    /// How many 裏ドラ _(ura dora)_ the hand holds, only counted after riichi
+    pub ura_dora: u8,
+    /// 本場 _(honba)_, repeat counters on the table
+    pub honba: u8,
+    /// 供託 _(kyoutaku)_, riichi sticks on the table that go to the winner
+    pub riichi_sticks: u8,
+}
+
+impl Win {
+    /// A win without any of the situational flags set, dora or sticks on the table
+    pub fn new(
+        concealed: Vec<Suit>,
+        melds: Vec<Meld>,
+        winning_tile: Suit,
+        win_type: WinType,
+        seat_wind: Wind,
+        round_wind: Wind,
+    ) -> Win {
+        Win {
+            concealed,
+            melds,
+            winning_tile,
+            win_type,
+            seat_wind,
+            round_wind,
+            riichi: Riichi::None,
+            ippatsu: false,
+            last_tile: false,
+            after_kan: false,
+            robbed_kan: false,
+            first_draw: false,
+            dora: 0,
+            ura_dora: 0,
+            honba: 0,
+            riichi_sticks: 0,
+        }
+    }
+
+    /// Whether the hand is 門前 _(menzen)_, i.e. has no called melds
+    pub fn is_closed(&self) -> bool {
+        self.melds.iter().all(|meld| !meld.is_open())
+    }
+
+    /// Whether the winner is the dealer, who always sits east
+    pub fn is_dealer(&self) -> bool {
+        self.seat_wind == Wind::East
+    }
+
+    /// Works out the yaku of every [Decomposition] of the hand. Can throw a
+    /// [DecodeErr] if the tiles don't add up to a hand
+    pub fn evaluate(&self) -> Result<Vec<Evaluation>, DecodeErr> {
+        // A kan takes a replacement tile, so counts as three towards the hand
+        let length = self.concealed.len() + 3 * self.melds.len();
+        if length != 14 {
+            return Err(DecodeErr::InvalidHandLength { length });
+        }
+
+        Ok(Decomposition::of(&self.concealed, self.winning_tile)?
+            .map(|decomposition| Evaluation {
+                yaku: self.yaku_of(&decomposition),
+                open: !self.is_closed(),
+                decomposition,
+            })
+            .collect())
+    }
+
+    /// The [Evaluation] worth the most yakuman, then the most han. `None` if the
+    /// hand isn't complete, the evaluation has no yaku if the hand has none.
+    /// Can throw a [DecodeErr]
+    pub fn yaku(&self) -> Result<Option<Evaluation>, DecodeErr> {
+        Ok(self
+            .evaluate()?
+            .into_iter()
+            .max_by_key(|evaluation| (evaluation.yakuman(), evaluation.han())))
+    }
+
+    fn yaku_of(&self, decomposition: &Decomposition) -> Vec<Yaku> {
+        let yakuman = self.yakuman(decomposition);
+        if !yakuman.is_empty() {
+            return yakuman;
+        }
+
+        let closed = self.is_closed();
+        let tsumo = self.win_type == WinType::Tsumo;
+        let mut yaku = vec![];
+
+        match self.riichi {
+            Riichi::Riichi => yaku.push(Yaku::Riichi),
+            Riichi::DoubleRiichi => yaku.push(Yaku::DoubleRiichi),
+            Riichi::None => {}
+        }
+        if self.ippatsu && self.riichi != Riichi::None {
+            yaku.push(Yaku::Ippatsu);
+        }
+        if closed && tsumo {
+            yaku.push(Yaku::MenzenTsumo);
+        }
+        if self.last_tile {
+            yaku.push(if tsumo {
+                Yaku::HaiteiRaoyue
+            } else {
+                Yaku::HouteiRaoyui
+            });
+        }
+        if self.after_kan && tsumo {
+            yaku.push(Yaku::RinshanKaihou);
+        }
+        if self.robbed_kan && !tsumo {
+            yaku.push(Yaku::Chankan);
+        }
+
+        let tiles = self.all_tiles();
+        if tiles.iter().all(Suit::is_simple) {
+            yaku.push(Yaku::Tanyao);
+        }
+        if tiles.iter().all(|tile| !tile.is_simple()) {
+            yaku.push(Yaku::Honroutou);
+        }
+        yaku.extend(flush(&tiles));
+
+        match &decomposition.shape {
+            Shape::SevenPairs(_) => yaku.push(Yaku::Chiitoitsu),
+            Shape::Standard { pair, groups } => {
+                yaku.extend(self.standard_yaku(decomposition, *pair, groups))
+            }
+            Shape::ThirteenOrphans { .. } => {}
+        }
+
+        yaku
+    }
+
+    fn standard_yaku(
+        &self,
+        decomposition: &Decomposition,
+        pair: Suit,
+        groups: &[Group],
+    ) -> Vec<Yaku> {
+        let closed = self.is_closed();
+        let blocks = self.blocks(decomposition, groups);
+        let sequences = blocks
+            .iter()
+            .filter_map(|block| match block.group {
+                Group::Sequence(tile) => Some(tile),
+                Group::Triplet(_) => None,
+            })
+            .collect::<Vec<_>>();
+        let triplets = blocks
+            .iter()
+            .filter_map(|block| match block.group {
+                Group::Triplet(tile) => Some(tile),
+                Group::Sequence(_) => None,
+            })
+            .collect::<Vec<_>>();
+
+        let mut yaku = vec![];
+
+        if closed
+            && sequences.len() == 4
+            && decomposition.wait == Wait::Ryanmen
+            && !self.is_value_tile(pair)
+        {
+            yaku.push(Yaku::Pinfu);
+        }
+
+        if closed {
+            let mut sorted = sequences.clone();
+            sorted.sort_unstable();
+            let mut identical = 0;
+            let mut i = 0;
+            while i + 1 < sorted.len() {
+                if sorted[i] == sorted[i + 1] {
+                    identical += 1;
+                    i += 2;
+                } else {
+                    i += 1;
+                }
+            }
+            match identical {
+                1 => yaku.push(Yaku::Iipeikou),
+                2 => yaku.push(Yaku::Ryanpeikou),
+                _ => {}
+            }
+        }
+
+        for tile in &triplets {
+            match tile {
+                Suit::Dragon(dragon) => yaku.push(Yaku::Dragon(*dragon)),
+                Suit::Wind(wind) => {
+                    if *wind == self.seat_wind {
+                        yaku.push(Yaku::SeatWind(*wind));
+                    }
+                    if *wind == self.round_wind {
+                        yaku.push(Yaku::RoundWind(*wind));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let outside = |tile: &Suit| !tile.is_simple();
+        let has_outside = |group: &Group| group.tiles().iter().any(outside);
+        if !sequences.is_empty() && outside(&pair) && blocks.iter().all(|b| has_outside(&b.group)) {
+            let honours = pair.is_honour() || triplets.iter().any(Suit::is_honour);
+            yaku.push(if honours { Yaku::Chanta } else { Yaku::Junchan });
+        }
+
+        let same_in_every_suit = |tiles: &[Suit]| {
+            tiles.iter().any(|tile| {
+                let number = tile.number();
+                number.is_some()
+                    && [Suit::Characters, Suit::Dots, Suit::Bamboo]
+                        .iter()
+                        .all(|suit| tiles.contains(&suit(number.unwrap())))
+            })
+        };
+        if same_in_every_suit(&sequences) {
+            yaku.push(Yaku::SanshokuDoujun);
+        }
+        if same_in_every_suit(&triplets) {
+            yaku.push(Yaku::SanshokuDoukou);
+        }
+
+        if [Suit::Characters, Suit::Dots, Suit::Bamboo]
+            .iter()
+            .any(|suit| [1, 4, 7].iter().all(|n| sequences.contains(&suit(*n))))
+        {
+            yaku.push(Yaku::Ittsu);
+        }
+
+        if triplets.len() == 4 {
+            yaku.push(Yaku::Toitoi);
+        }
+        if blocks
+            .iter()
+            .filter(|block| block.concealed_triplet())
+            .count()
+            == 3
+        {
+            yaku.push(Yaku::Sanankou);
+        }
+        if blocks.iter().filter(|block| block.kan).count() == 3 {
+            yaku.push(Yaku::Sankantsu);
+        }
+
+        let dragons = triplets
+            .iter()
+            .filter(|tile| matches!(tile, Suit::Dragon(_)));
+        if dragons.count() == 2 && matches!(pair, Suit::Dragon(_)) {
+            yaku.push(Yaku::Shousangen);
+        }
+
+        yaku
+    }
+
+    fn yakuman(&self, decomposition: &Decomposition) -> Vec<Yaku> {
+        let tiles = self.all_tiles();
+        let mut yaku = vec![];
+
+        if self.first_draw && self.win_type == WinType::Tsumo && self.melds.is_empty() {
+            yaku.push(if self.is_dealer() {
+                Yaku::Tenhou
+            } else {
+                Yaku::Chiihou
+            });
+        }
+
+        if tiles.iter().all(Suit::is_honour) {
+            yaku.push(Yaku::Tsuuiisou);
+        }
+        if tiles.iter().all(Suit::is_terminal) {
+            yaku.push(Yaku::Chinroutou);
+        }
+        if tiles.iter().all(|tile| {
+            matches!(
+                tile.normalize(),
+                Suit::Bamboo(2 | 3 | 4 | 6 | 8) | Suit::Dragon(Dragon::Green)
+            )
+        }) {
+            yaku.push(Yaku::Ryuuiisou);
+        }
+
+        match &decomposition.shape {
+            Shape::ThirteenOrphans { .. } => yaku.push(Yaku::KokushiMusou),
+            Shape::SevenPairs(_) => {}
+            Shape::Standard { pair, groups } => {
+                let blocks = self.blocks(decomposition, groups);
+                let triplet = |block: &&Block| matches!(block.group, Group::Triplet(_));
+                let honour = |kind: fn(&Suit) -> bool| {
+                    blocks
+                        .iter()
+                        .filter(triplet)
+                        .filter(|block| kind(&block.group.tiles()[0]))
+                        .count()
+                };
+                let wind = |tile: &Suit| matches!(tile, Suit::Wind(_));
+                let dragon = |tile: &Suit| matches!(tile, Suit::Dragon(_));
+
+                if blocks
+                    .iter()
+                    .filter(|block| block.concealed_triplet())
+                    .count()
+                    == 4
+                {
+                    yaku.push(Yaku::Suuankou);
+                }
+                if honour(dragon) == 3 {
+                    yaku.push(Yaku::Daisangen);
+                }
+                if honour(wind) == 4 {
+                    yaku.push(Yaku::Daisuushii);
+                } else if honour(wind) == 3 && wind(pair) {
+                    yaku.push(Yaku::Shousuushii);
+                }
+                if blocks.iter().filter(|block| block.kan).count() == 4 {
+                    yaku.push(Yaku::Suukantsu);
+                }
+                if self.melds.is_empty() && nine_gates(&tiles) {
+                    yaku.push(Yaku::ChuurenPoutou);
+                }
+            }
+        }
+
+        yaku
+    }
+
+    /// Every group in the hand, concealed and called
+    pub(crate) fn blocks(&self, decomposition: &Decomposition, groups: &[Group]) -> Vec<Block> {
+        let winning = decomposition.winning_tile.normalize();
+        let mut opened = self.win_type == WinType::Ron && decomposition.wait == Wait::Shanpon;
+
+        let mut blocks = groups
+            .iter()
+            .map(|group| {
+                let concealed = !(opened && *group == Group::Triplet(winning));
+                opened &= concealed;
+                Block {
+                    group: *group,
+                    concealed,
+                    kan: false,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        blocks.extend(self.melds.iter().map(|meld| Block {
+            group: meld.group(),
+            concealed: !meld.is_open(),
+            kan: meld.is_kan(),
+        }));
+
+        blocks
+    }
+
+    pub(crate) fn all_tiles(&self) -> Vec<Suit> {
+        self.concealed
+            .iter()
+            .chain(self.melds.iter().flat_map(|meld| meld.tiles()))
+            .copied()
+            .collect()
+    }
+
+    /// Whether a pair of `tile` would be worth fu, so can't be part of pinfu
+    fn is_value_tile(&self, tile: Suit) -> bool {
+        match tile {
+            Suit::Dragon(_) => true,
+            Suit::Wind(wind) => wind == self.seat_wind || wind == self.round_wind,
+            _ => false,
+        }
+    }
+}
+
+/// A group along with how it was made
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct Block {
+    pub(crate) group: Group,
+    pub(crate) concealed: bool,
+    pub(crate) kan: bool,
+}
+
+impl Block {
+    fn concealed_triplet(&self) -> bool {
+        self.concealed && matches!(self.group, Group::Triplet(_))
+    }
+}
+
+fn flush(tiles: &[Suit]) -> Option<Yaku> {
+    let mut suits = tiles.iter().filter_map(|tile| match tile {
+        Suit::Characters(_) => Some(0),
+        Suit::Dots(_) => Some(1),
+        Suit::Bamboo(_) => Some(2),
+        _ => None,
+    });
+
+    let first = suits.next()?;
+    if !suits.all(|suit| suit == first) {
+        return None;
+    }
+
+    if tiles.iter().any(Suit::is_honour) {
+        Some(Yaku::Honitsu)
+    } else {
+        Some(Yaku::Chinitsu)
+    }
+}
+
+/// 1112345678999 in a single suit, plus any other tile of that suit
+fn nine_gates(tiles: &[Suit]) -> bool {
+    if flush(tiles) != Some(Yaku::Chinitsu) {
+        return false;
+    }
+
+    let mut counts = [0u8; 10];
+    tiles
+        .iter()
+        .filter_map(Suit::number)
+        .for_each(|n| counts[n as usize] += 1);
+
+    counts[1] >= 3 && counts[9] >= 3 && counts[2..9].iter().all(|count| *count >= 1)
+}
+
+/// 役 _(yaku)_,
+/// the patterns that make a hand worth points
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Yaku {
+    /// 立直 _(riichi)_
+    Riichi,
+    /// ダブル立直 _(daburu riichi)_
+    DoubleRiichi,
+    /// 一発 _(ippatsu)_
+    Ippatsu,
+    /// 門前清自摸和 _(menzen tsumo)_
+    MenzenTsumo,
+    /// 平和 _(pinfu)_
+    Pinfu,
+    /// 断幺九 _(tanyao)_
+    Tanyao,
+    /// 一盃口 _(iipeikou)_
+    Iipeikou,
+    /// 役牌 _(yakuhai)_, a triplet of a dragon
+    Dragon(Dragon),
+    /// 役牌 _(yakuhai)_, a triplet of the seat wind
+    SeatWind(Wind),
+    /// 役牌 _(yakuhai)_, a triplet of the round wind
+    RoundWind(Wind),
+    /// 海底摸月 _(haitei raoyue)_
+    HaiteiRaoyue,
+    /// 河底撈魚 _(houtei raoyui)_
+    HouteiRaoyui,
+    /// 嶺上開花 _(rinshan kaihou)_
+    RinshanKaihou,
+    /// 搶槓 _(chankan)_
+    Chankan,
+    /// 七対子 _(chiitoitsu)_
+    Chiitoitsu,
+    /// 混全帯幺九 _(chanta)_
+    Chanta,
+    /// 三色同順 _(sanshoku doujun)_
+    SanshokuDoujun,
+    /// 一気通貫 _(ittsu)_
+    Ittsu,
+    /// 対々和 _(toitoi)_
+    Toitoi,
+    /// 三暗刻 _(sanankou)_
+    Sanankou,
+    /// 三色同刻 _(sanshoku doukou)_
+    SanshokuDoukou,
+    /// 三槓子 _(sankantsu)_
+    Sankantsu,
+    /// 小三元 _(shousangen)_
+    Shousangen,
+    /// 混老頭 _(honroutou)_
+    Honroutou,
+    /// 二盃口 _(ryanpeikou)_
+    Ryanpeikou,
+    /// 純全帯幺九 _(junchan)_
+    Junchan,
+    /// 混一色 _(honitsu)_
+    Honitsu,
+    /// 清一色 _(chinitsu)_
+    Chinitsu,
+    /// 国士無双 _(kokushi musou)_
+    KokushiMusou,
+    /// 四暗刻 _(suuankou)_
+    Suuankou,
+    /// 大三元 _(daisangen)_
+    Daisangen,
+    /// 小四喜 _(shousuushii)_
+    Shousuushii,
+    /// 大四喜 _(daisuushii)_
+    Daisuushii,
+    /// 字一色 _(tsuuiisou)_
+    Tsuuiisou,
+    /// 清老頭 _(chinroutou)_
+    Chinroutou,
+    /// 緑一色 _(ryuuiisou)_
+    Ryuuiisou,
+    /// 九蓮宝燈 _(chuuren poutou)_
+    ChuurenPoutou,
+    /// 四槓子 _(suukantsu)_
+    Suukantsu,
+    /// 天和 _(tenhou)_
+    Tenhou,
+    /// 地和 _(chiihou)_
+    Chiihou,
+}
+
+impl Yaku {
+    /// How many han the yaku is worth, `0` for yakuman and for yaku that need a
+    /// closed hand when `open` is set
+    ///
+    /// ```
+    /// # use mahjong_encoding::*;
+    /// assert_eq!(Yaku::Honitsu.han(false), 3);
+    /// assert_eq!(Yaku::Honitsu.han(true), 2);
+    /// assert_eq!(Yaku::Pinfu.han(true), 0);
+    /// ```
+    pub fn han(&self, open: bool) -> u8 {
+        let (closed, called) = match self {
+            Yaku::Riichi | Yaku::Ippatsu | Yaku::MenzenTsumo | Yaku::Pinfu | Yaku::Iipeikou => {
+                (1, 0)
+            }
+            Yaku::Tanyao
+            | Yaku::Dragon(_)
+            | Yaku::SeatWind(_)
+            | Yaku::RoundWind(_)
+            | Yaku::HaiteiRaoyue
+            | Yaku::HouteiRaoyui
+            | Yaku::RinshanKaihou
+            | Yaku::Chankan => (1, 1),
+            Yaku::DoubleRiichi | Yaku::Chiitoitsu => (2, 0),
+            Yaku::Chanta | Yaku::SanshokuDoujun | Yaku::Ittsu => (2, 1),
+            Yaku::Toitoi
+            | Yaku::Sanankou
+            | Yaku::SanshokuDoukou
+            | Yaku::Sankantsu
+            | Yaku::Shousangen
+            | Yaku::Honroutou => (2, 2),
+            Yaku::Ryanpeikou => (3, 0),
+            Yaku::Junchan | Yaku::Honitsu => (3, 2),
+            Yaku::Chinitsu => (6, 5),
+            _ => (0, 0),
+        };
+
+        if open {
+            called
+        } else {
+            closed
+        }
+    }
+
+    /// Whether the yaku is a 役満 _(yakuman)_
+    pub fn is_yakuman(&self) -> bool {
+        matches!(
+            self,
+            Yaku::KokushiMusou
+                | Yaku::Suuankou
+                | Yaku::Daisangen
+                | Yaku::Shousuushii
+                | Yaku::Daisuushii
+                | Yaku::Tsuuiisou
+                | Yaku::Chinroutou
+                | Yaku::Ryuuiisou
+                | Yaku::ChuurenPoutou
+                | Yaku::Suukantsu
+                | Yaku::Tenhou
+                | Yaku::Chiihou
+        )
+    }
+}
+
+/// The yaku of one [Decomposition] of a winning hand, see [Win::evaluate]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Evaluation {
+    /// The reading of the hand
+    pub decomposition: Decomposition,
+    /// Every yaku the reading scores, only yakuman if there are any
+    pub yaku: Vec<Yaku>,
+    /// Whether the hand has called melds
+    pub open: bool,
+}
+
+impl Evaluation {
+    /// Total han from yaku, not counting dora
+    pub fn han(&self) -> u8 {
+        self.yaku.iter().map(|yaku| yaku.han(self.open)).sum()
+    }
+
+    /// How many yakuman the hand is worth
+    pub fn yakuman(&self) -> u8 {
+        self.yaku.iter().filter(|yaku| yaku.is_yakuman()).count() as u8
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn win(hand: &str, winning_tile: &str, win_type: WinType) -> Win {
+        Win::new(
+            Suit::from_mpsz(hand).unwrap(),
+            vec![],
+            Suit::from_mpsz(winning_tile).unwrap()[0],
+            win_type,
+            Wind::South,
+            Wind::East,
+        )
+    }
+
+    fn yaku(win: &Win) -> Vec<Yaku> {
+        win.yaku().unwrap().unwrap().yaku
+    }
+
+    #[test]
+    fn finds_pinfu_tanyao_and_iipeikou() {
+        let win = win("223344m456p678s55p", "2m", WinType::Ron);
+
+        assert_eq!(yaku(&win), [Yaku::Tanyao, Yaku::Pinfu, Yaku::Iipeikou]);
+    }
+
+    #[test]
+    fn finds_yakuhai_for_seat_and_round_winds() {
+        let mut win = win("123m456p78s111z55z9s", "9s", WinType::Ron);
+        win.seat_wind = Wind::East;
+
+        assert_eq!(
+            yaku(&win),
+            [Yaku::SeatWind(Wind::East), Yaku::RoundWind(Wind::East)]
+        );
+    }
+
+    #[test]
+    fn finds_riichi_tsumo_and_situational_yaku() {
+        let mut win = win("123m456p789s11z234m", "4m", WinType::Tsumo);
+        win.riichi = Riichi::Riichi;
+        win.ippatsu = true;
+        win.last_tile = true;
+
+        assert_eq!(
+            yaku(&win),
+            [Yaku::Riichi, Yaku::Ippatsu, Yaku::MenzenTsumo, Yaku::HaiteiRaoyue]
+        );
+    }
+
+    #[test]
+    fn finds_flushes_and_outside_hands() {
+        let found = yaku(&win("12378911178999m", "9m", WinType::Ron));
+        assert!(found.contains(&Yaku::Chinitsu));
+        assert!(found.contains(&Yaku::Junchan));
+
+        let found = yaku(&win("123789m999m777z11z", "1z", WinType::Ron));
+        assert!(found.contains(&Yaku::Honitsu));
+        assert!(found.contains(&Yaku::Chanta));
+        assert!(found.contains(&Yaku::Dragon(Dragon::Red)));
+    }
+
+    #[test]
+    fn finds_sanshoku_ittsu_and_toitoi() {
+        let found = yaku(&win("123m123p123s789m55z", "5z", WinType::Ron));
+        assert!(found.contains(&Yaku::SanshokuDoujun));
+
+        let found = yaku(&win("123456789p222m11z", "1z", WinType::Ron));
+        assert!(found.contains(&Yaku::Ittsu));
+
+        let mut toitoi = win("222p33s", "2p", WinType::Ron);
+        toitoi.melds = ["111m", "999s", "444p"]
+            .iter()
+            .map(|meld| Meld::new(MeldKind::Pon, Suit::from_mpsz(meld).unwrap()).unwrap())
+            .collect();
+        let found = yaku(&toitoi);
+        assert!(found.contains(&Yaku::Toitoi));
+        assert!(!found.contains(&Yaku::Sanankou));
+    }
+
+    #[test]
+    fn concealed_triplets_depend_on_how_the_hand_was_won() {
+        let tsumo = yaku(&win("111m222p333s44z567p", "3s", WinType::Tsumo));
+        assert!(tsumo.contains(&Yaku::Sanankou));
+
+        let ron = yaku(&win("111m222p333s44z567p", "3s", WinType::Ron));
+        assert!(!ron.contains(&Yaku::Sanankou));
+
+        let suuankou = yaku(&win("111m222p333s444z55z", "5z", WinType::Ron));
+        assert_eq!(suuankou, [Yaku::Suuankou]);
+    }
+
+    #[test]
+    fn finds_chiitoitsu_and_yakuman() {
+        let found = yaku(&win("1122m3344p5566s77z", "7z", WinType::Ron));
+        assert!(found.contains(&Yaku::Chiitoitsu));
+
+        let found = yaku(&win("19m19p19s12345677z", "1m", WinType::Ron));
+        assert_eq!(found, [Yaku::KokushiMusou]);
+
+        let found = yaku(&win("555666777z11m234s", "2s", WinType::Ron));
+        assert_eq!(found, [Yaku::Daisangen]);
+
+        let found = yaku(&win("11123455678999m", "5m", WinType::Ron));
+        assert!(found.contains(&Yaku::ChuurenPoutou));
+    }
+
+    #[test]
+    fn hands_without_yaku_are_still_evaluated() {
+        let mut win = win("123m456p789s22z", "9s", WinType::Ron);
+        win.melds = vec![Meld::new(MeldKind::Chi, Suit::from_mpsz("234s").unwrap()).unwrap()];
+
+        assert!(yaku(&win).is_empty());
+    }
+
+    #[test]
+    fn rejects_hands_that_dont_add_up() {
+        let mut win = win("123m456p789s22z", "9s", WinType::Ron);
+        assert_eq!(win.yaku(), Err(DecodeErr::InvalidHandLength { length: 11 }));
+
+        win.melds = vec![
+            Meld::new(MeldKind::Chi, Suit::from_mpsz("234s").unwrap()).unwrap(),
+            Meld::new(MeldKind::OpenKan, Suit::from_mpsz("5555z").unwrap()).unwrap(),
+        ];
+        assert_eq!(win.yaku(), Err(DecodeErr::InvalidHandLength { length: 17 }));
+    }
+}